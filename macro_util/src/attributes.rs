@@ -7,24 +7,31 @@ use syn::{
     Attribute, Ident, Lit, LitStr, Meta, NestedMeta, Path,
 };
 
+use crate::structures::{Checks, Choice, OnlyIn, Permissions};
+use crate::util::AsOption;
 use crate::util::LitExt;
 
 // line 73
 pub fn parse_values(attr: &Attribute) -> Result<Values> {
     let meta = attr.parse_meta()?;
 
+    parse_meta_values(meta, attr.span())
+}
+
+// also used for `#[application_command(<meta>, ...)]` arguments.
+pub fn parse_meta_values(meta: Meta, span: Span) -> Result<Values> {
     match meta {
         Meta::Path(path) => {
             let name = to_ident(path)?;
 
-            Ok(Values::new(name, ValueKind::Name, Vec::new(), attr.span()))
+            Ok(Values::new(name, ValueKind::Name, Vec::new(), span))
         }
         Meta::List(meta) => {
             let name = to_ident(meta.path)?;
             let nested = meta.nested;
 
             if nested.is_empty() {
-                return Err(Error::new(attr.span(), "list cannot be empty"));
+                return Err(Error::new(span, "list cannot be empty"));
             }
 
             let mut lits = Vec::with_capacity(nested.len());
@@ -37,8 +44,21 @@ pub fn parse_values(attr: &Attribute) -> Result<Values> {
                             let i = to_ident(path)?;
                             lits.push(Lit::Str(LitStr::new(&i.to_string(), i.span())))
                         }
-                        Meta::List(_) | Meta::NameValue(_) => {
-                            return Err(Error::new(attr.span(), "cannot nest a list; only accept literals and identifiers at this level"))
+                        // `guild(<id>, ...)` is flattened to `"guild(<id>)"` for each id.
+                        Meta::List(list) => {
+                            let i = to_ident(list.path)?;
+                            for nested in list.nested {
+                                match nested {
+                                    NestedMeta::Lit(Lit::Int(id)) => lits.push(Lit::Str(LitStr::new(
+                                        &format!("{}({})", i, id.base10_digits()),
+                                        id.span(),
+                                    ))),
+                                    _ => return Err(Error::new(span, "cannot nest a list deeper; only accept integers at this level")),
+                                }
+                            }
+                        }
+                        Meta::NameValue(_) => {
+                            return Err(Error::new(span, "cannot nest a name value; only accept literals, identifiers and lists at this level"))
                         }
                     },
                 }
@@ -50,12 +70,12 @@ pub fn parse_values(attr: &Attribute) -> Result<Values> {
                 ValueKind::List
             };
 
-            Ok(Values::new(name, kind, lits, attr.span()))
+            Ok(Values::new(name, kind, lits, span))
         }
         Meta::NameValue(meta) => {
             let name = to_ident(meta.path)?;
             let lit = meta.lit;
-            Ok(Values::new(name, ValueKind::Equals, vec![lit], attr.span()))
+            Ok(Values::new(name, ValueKind::Equals, vec![lit], span))
         }
     }
 }
@@ -102,7 +122,7 @@ impl fmt::Display for ValueKind {
         match self {
             ValueKind::Name => f.pad("`#[<name>]`"),
             ValueKind::Equals => f.pad("`#[<name> = <value>]`"),
-            ValueKind::List => f.pad("`#[<name>([<value>, <value>, <value>, ...])]"),
+            ValueKind::List => f.pad("`#[<name>([<value>, <value>, <value>, ...])]`"),
             ValueKind::SingleList => f.pad("`#[<name>(<value>)]`"),
         }
     }
@@ -200,6 +220,113 @@ impl AttributeOption for String {
     fn parse(values: Values) -> Result<Self> {
         validate(&values, &[ValueKind::Equals, ValueKind::SingleList])?;
 
-        Ok(values.literals[0].to_str())
+        values.literals[0].to_str()
+    }
+}
+
+impl AttributeOption for Vec<String> {
+    #[inline]
+    fn parse(values: Values) -> Result<Self> {
+        validate(&values, &[ValueKind::List])?;
+
+        values.literals.iter().map(|l| l.to_str()).collect()
+    }
+}
+
+impl AttributeOption for bool {
+    #[inline]
+    fn parse(values: Values) -> Result<Self> {
+        validate(&values, &[ValueKind::Name, ValueKind::SingleList])?;
+
+        // `#[<name>]` mean true
+        match values.literals.first() {
+            Some(l) => l.to_bool(),
+            None => Ok(true),
+        }
+    }
+}
+
+impl AttributeOption for u64 {
+    #[inline]
+    fn parse(values: Values) -> Result<Self> {
+        validate(&values, &[ValueKind::Equals, ValueKind::SingleList])?;
+
+        values.literals[0].to_u64()
+    }
+}
+
+impl<T: AttributeOption> AttributeOption for AsOption<T> {
+    #[inline]
+    fn parse(values: Values) -> Result<Self> {
+        Ok(AsOption(Some(T::parse(values)?)))
+    }
+}
+
+impl AttributeOption for Checks {
+    #[inline]
+    fn parse(values: Values) -> Result<Self> {
+        <Vec<Ident> as AttributeOption>::parse(values).map(Checks)
+    }
+}
+
+impl AttributeOption for Permissions {
+    fn parse(values: Values) -> Result<Self> {
+        validate(&values, &[ValueKind::List])?;
+
+        let mut permissions = Permissions::default();
+
+        for lit in &values.literals {
+            match Permissions::from_str(&lit.to_str()?) {
+                Some(p) => permissions.0 |= p.0,
+                None => return Err(Error::new(lit.span(), "invalid permission")),
+            }
+        }
+
+        Ok(permissions)
+    }
+}
+
+impl AttributeOption for Vec<OnlyIn> {
+    fn parse(values: Values) -> Result<Self> {
+        validate(&values, &[ValueKind::List, ValueKind::SingleList])?;
+
+        values
+            .literals
+            .iter()
+            .map(|l| OnlyIn::from_str(&l.to_str()?, l.span()))
+            .collect()
+    }
+}
+
+impl AttributeOption for Choice {
+    fn parse(values: Values) -> Result<Self> {
+        validate(&values, &[ValueKind::List])?;
+
+        if values.literals.len() != 2 {
+            return Err(Error::new(
+                values.span,
+                "expected a choice name and value: `#[choice(\"<name>\", <value>)]`",
+            ));
+        }
+
+        let name = match &values.literals[0] {
+            Lit::Str(s) => s.value(),
+            lit => return Err(Error::new(lit.span(), "choice name must be a string")),
+        };
+
+        Ok(Choice {
+            name,
+            value: values.literals[1].clone(),
+            span: values.span,
+        })
+    }
+}
+
+impl AttributeOption for Vec<Ident> {
+    #[inline]
+    fn parse(values: Values) -> Result<Self> {
+        validate(&values, &[ValueKind::List])?;
+
+        values.literals.iter().map(|l| l.to_ident()).collect()
     }
 }
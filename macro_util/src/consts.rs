@@ -1,10 +1,14 @@
 pub mod suffixes {
-    pub const COMMAND: &str = "COMMAND";
-    pub const COMMAND_OPTIONS: &str = "COMMAND_OPTIONS";
-    pub const HELP_OPTIONS: &str = "OPTIONS";
-    pub const GROUP: &str = "GROUP";
-    pub const GROUP_OPTIONS: &str = "GROUP_OPTIONS";
     pub const CHECK: &str = "CHECK";
+    pub const COMPONENT: &str = "COMPONENT";
+    pub const APPLICATION_COMMAND: &str = "Command";
 }
 
 pub use self::suffixes::*;
+
+// discord limits
+pub const MAX_CHOICES: usize = 25;
+pub const MAX_OPTIONS: usize = 25;
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_DESCRIPTION_LENGTH: usize = 100;
+pub const MAX_CUSTOM_ID_LENGTH: usize = 100;
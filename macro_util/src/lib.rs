@@ -2,7 +2,7 @@ use proc_macro::TokenStream;
 #[allow(unused_imports)]
 use proc_macro2::Span;
 #[allow(unused_imports)]
-use quote::quote;
+use quote::{quote, quote_spanned};
 #[allow(unused_imports)]
 use syn::{
     parse::{Error, Parse, ParseStream, Result},
@@ -12,107 +12,681 @@ use syn::{
     Ident, Lit, Token,
 };
 
+#[macro_use]
+pub(crate) mod util;
+
 pub(crate) mod attributes;
 pub(crate) mod consts;
 pub(crate) mod structures;
 
-#[macro_use]
-pub(crate) mod util;
-
-use attributes::parse_values;
-use structures::{CommandFun, Options};
-use util::{IdentExt2, LitExt};
+use attributes::{parse_values, Values};
+use consts::{APPLICATION_COMMAND, MAX_OPTIONS};
+use structures::{
+    command_scope, CheckFun, CommandArgs, CommandFun, CommandOption, ComponentFun, OptionKind,
+    Options,
+};
+use util::{
+    create_declaration_validations, is_responder, validate_context_menu_name, validate_description,
+    validate_name, Errors, IdentExt2, LitExt,
+};
 
 // define macro
 macro_rules! match_options {
-    ($v:expr, $values:ident, $options:ident, $span:expr => [$($name:ident);*]) => {
+    ($v:expr, $values:ident, $options:ident, $errors:ident, $span:expr => [$($name:ident);*]) => {
         match $v {
             $(
-                stringify!($name) => $options.$name = propagate_err!($crate::attributes::parse($values)),
+                stringify!($name) => {
+                    if let Some(v) = collect_err!($errors, $crate::attributes::parse($values)) {
+                        $options.$name = v;
+                    }
+                }
             )*
-            _ => {
-                return Error::new($span, format_args!("invalid attribute: {:?}", $v))
-                    .to_compile_error()
-                    .into();
-            },
+            _ => $errors.push(Error::new($span, format_args!("invalid attribute: {:?}", $v))),
         }
     };
 }
 
-// define proc macro
-#[proc_macro_attribute]
-pub fn application_command(attr: TokenStream, input: TokenStream) -> TokenStream {
-    // TODO: remove
-    dbg!(&input);
-    dbg!(&attr);
+// attributes of serenity's standard framework, which have no meaning here.
+const UNSUPPORTED_ATTRIBUTES: &[&str] = &[
+    "owners_only",
+    "owner_privilege",
+    "allowed_roles",
+    "bucket",
+    "aliases",
+    "delimiters",
+    "usage",
+    "example",
+    "help_available",
+    "min_args",
+    "max_args",
+    "num_args",
+];
 
-    // input parse to Command Fun
-    let mut fun = parse_macro_input!(input as CommandFun);
+// `#[application_command(...)]` arguments and `#[...]` attributes, and the span of description.
+fn parse_options(
+    args: Vec<Values>,
+    fun: &CommandFun,
+    errors: &mut Errors,
+) -> (Options, Option<Span>) {
+    // point to doc comment, or function name if no doc comment.
+    let mut description_span = None;
 
-    // TODO: remove
-    dbg!(&fun);
-
-    // TODO: maybe set name?
-    let _name = if !attr.is_empty() {
-        parse_macro_input!(attr as Lit).to_str()
-    } else {
-        fun.name.to_string_non_raw()
-    };
-
-    let mut options = Options::new();
+    let mut options = Options::default();
 
+    // `#[application_command(...)]` arguments and `#[...]` attributes are same.
+    let mut all_values = args;
     for attribute in &fun.attributes {
-        let span = attribute.span();
-        let values = propagate_err!(parse_values(attribute));
+        all_values.extend(collect_err!(errors, parse_values(attribute)));
+    }
+
+    for values in all_values {
+        let span = values.span;
 
         let name = values.name.to_string();
         let name = &name[..];
 
         match name {
-            // num_args and example are not necessary,
             "description" => {
-                let line: String = propagate_err!(attributes::parse(values));
-                util::append_line(&mut options.description, line);
+                description_span.get_or_insert(span);
+                let line: Option<String> = collect_err!(errors, attributes::parse(values));
+                if let Some(line) = line {
+                    util::append_line(&mut options.description, line);
+                }
             }
+            // prefix command attributes. silently ignoring access control is dangerous.
+            name if UNSUPPORTED_ATTRIBUTES.contains(&name) => errors.push(Error::new(
+                values.name.span(),
+                format_args!("`#[{}]` is not supported for application commands", name),
+            )),
             _ => {
-                // min_args;
-                // max_args;
-                match_options!(name, values, options, span => [
+                match_options!(name, values, options, errors, span => [
                     checks;
-                    bucket;
-                    aliases;
-                    delimiters;
-                    usage;
                     required_permissions;
-                    allowed_roles;
-                    help_available;
                     only_in;
-                    owners_only;
-                    owner_privilege;
-                    sub_commands
+                    sub_commands;
+                    cooldown;
+                    ephemeral;
+                    defer
                 ]);
             }
         }
     }
 
+    (options, description_span)
+}
+
+// `CommandMeta` of the command.
+fn command_meta(
+    name: &str,
+    description: &str,
+    kind: proc_macro2::TokenStream,
+    scope: proc_macro2::TokenStream,
+    options: &Options,
+) -> proc_macro2::TokenStream {
     let Options {
         checks,
-        bucket,
-        aliases,
-        description,
-        delimiters,
-        usage,
-        examples,
-        allowed_roles,
         required_permissions,
-        help_available,
-        only_in,
-        owners_only,
-        owner_privilege,
-        sub_commands,
+        cooldown,
+        ephemeral,
+        defer,
+        ..
     } = options;
 
-    propagate_err!(create_declaration_validations());
+    let cooldown = match cooldown.0 {
+        Some(secs) => quote!(Some(std::time::Duration::from_secs(#secs))),
+        None => quote!(None),
+    };
+
+    quote! {
+        crate::app_cmd::CommandMeta {
+            name: #name,
+            description: #description,
+            kind: #kind,
+            scope: #scope,
+            checks: #checks,
+            cooldown: #cooldown,
+            ephemeral: #ephemeral,
+            defer: #defer,
+            required_permissions: #required_permissions,
+        }
+    }
+}
+
+// `crate::app_cmd_model::Command` of the command, `kind` is its variant name.
+fn command_model(
+    name: &str,
+    description: &str,
+    kind: &str,
+    command_options: Vec<proc_macro2::TokenStream>,
+    options: &Options,
+) -> proc_macro2::TokenStream {
+    let path = quote!(crate::app_cmd_model);
+    let kind = Ident::new(kind, Span::call_site());
+
+    // members without these permissions cannot see the command by default.
+    let permissions = match options.required_permissions.0 {
+        0 => quote!(None),
+        bits => quote!(Some(#path::Permissions(#bits))),
+    };
+
+    quote! {
+        #path::Command {
+            kind: #path::CommandKind::#kind,
+            name: #name.to_string(),
+            description: #description.to_string(),
+            options: vec![#(#command_options),*],
+            default_member_permissions: #permissions,
+        }
+    }
+}
+
+// what slash and context menu commands have in common.
+struct CommandInput {
+    fun: CommandFun,
+    name: String,
+    options: Options,
+    // doc comment, or function name if no doc comment.
+    description_span: Span,
+    scope: proc_macro2::TokenStream,
+    // reported by the caller, together with its own errors.
+    errors: Errors,
+}
+
+impl CommandInput {
+    // use function name if name is not set. `validate_name` differs by command type.
+    fn parse(
+        attr: TokenStream,
+        input: TokenStream,
+        validate_name: fn(&str, Span) -> Result<()>,
+    ) -> Result<Self> {
+        let fun = syn::parse::<CommandFun>(input)?;
+        let CommandArgs {
+            name,
+            values,
+            mut errors,
+        } = syn::parse::<CommandArgs>(attr)?;
+
+        let (name, name_span) = match &name {
+            Some(lit) => (
+                collect_err!(errors, lit.to_str()).unwrap_or_default(),
+                lit.span(),
+            ),
+            None => (fun.name.to_string_non_raw(), fun.name.span()),
+        };
+        collect_err!(errors, validate_name(&name, name_span));
+
+        let (options, description_span) = parse_options(values, &fun, &mut errors);
+
+        // only used for top level commands.
+        let scope = collect_err!(errors, command_scope(&options.only_in, fun.name.span()))
+            .unwrap_or_default();
+
+        // arguments cannot be checked without context and interaction arguments.
+        if let Err(e) = create_declaration_validations(&fun) {
+            errors.push(e);
+            errors.finish()?;
+        }
+
+        Ok(CommandInput {
+            description_span: description_span.unwrap_or_else(|| fun.name.span()),
+            fun,
+            name,
+            options,
+            scope,
+            errors,
+        })
+    }
+}
+
+// arguments of `options_handler`.
+// mixed site, so never conflict with user's items.
+struct HandlerArgs {
+    ctx: Ident,
+    interaction: Ident,
+    responder: Ident,
+    options: Ident,
+}
+
+impl HandlerArgs {
+    // `options` is `_options` if the handler does not use it.
+    fn new(options: &str) -> Self {
+        HandlerArgs {
+            ctx: Ident::new("ctx", Span::mixed_site()),
+            interaction: Ident::new("interaction", Span::mixed_site()),
+            responder: Ident::new("responder", Span::mixed_site()),
+            options: Ident::new(options, Span::mixed_site()),
+        }
+    }
+
+    // arguments of the command function after context and interaction, in declared order.
+    // responder is passed as is, and `values` fill the others.
+    fn call_args(&self, fun: &CommandFun, mut values: impl Iterator<Item = Ident>) -> Vec<Ident> {
+        fun.args[2..]
+            .iter()
+            .map(|arg| match is_responder(&arg.kind) {
+                true => self.responder.clone(),
+                false => values.next().unwrap(),
+            })
+            .collect()
+    }
+}
+
+// `ApplicationCommandTrait` impl of the command, and the function itself.
+// `items` are other trait items, `body` is of `options_handler`.
+fn command_impl(
+    fun: &CommandFun,
+    meta: proc_macro2::TokenStream,
+    model: proc_macro2::TokenStream,
+    items: proc_macro2::TokenStream,
+    args: &HandlerArgs,
+    body: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let visibility = &fun.visibility;
+    let struct_name = fun.name.with_camel_suffix(APPLICATION_COMMAND);
+    let HandlerArgs {
+        ctx,
+        interaction,
+        responder,
+        options,
+    } = args;
+
+    quote! {
+        #visibility struct #struct_name;
+
+        #[serenity::async_trait]
+        impl crate::app_cmd::ApplicationCommandTrait for #struct_name {
+            const META: crate::app_cmd::CommandMeta = #meta;
+            #items
+
+            fn model() -> crate::app_cmd_model::Command {
+                #model
+            }
+
+            async fn options_handler(
+                #ctx: &serenity::client::Context,
+                #interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+                #responder: &crate::app_cmd::InteractionResponder<'_>,
+                #options: &[serenity::model::interactions::application_command::ApplicationCommandInteractionDataOption],
+            ) -> crate::app_cmd::CommandResult {
+                #body
+            }
+        }
+
+        // collected by `crate::app_cmd::commands`, so it is not listed by hand.
+        ::inventory::submit! {
+            crate::app_cmd::ApplicationCommand::new::<#struct_name>()
+        }
+
+        #fun
+    }
+}
+
+// define proc macro
+#[proc_macro_attribute]
+pub fn application_command(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let CommandInput {
+        fun,
+        name,
+        options,
+        description_span,
+        scope,
+        // report every attribute error together.
+        mut errors,
+    } = propagate_err!(CommandInput::parse(attr, input, validate_name));
+
+    let Options {
+        description,
+        sub_commands,
+        ..
+    } = &options;
+    let description = description.0.clone().unwrap_or_default();
+    let description = description.trim_end();
+    collect_err!(errors, validate_description(description, description_span));
+
+    // checked by the parent, if it is a sub command.
+    let scoped = if options.only_in.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            const SCOPED: bool = true;
+        }
+    };
+
+    // first two arguments are context and interaction.
+    // the others are options, except the responder.
+    let option_args = fun.args[2..]
+        .iter()
+        .filter(|arg| !is_responder(&arg.kind))
+        .collect::<Vec<_>>();
+    let command_options = option_args
+        .iter()
+        .filter_map(|arg| collect_err!(errors, CommandOption::new(arg)))
+        .collect::<Vec<_>>();
+
+    // discord rejects a required option after an optional one.
+    // types are checked again, but their errors are already reported above.
+    let mut optional = false;
+    for arg in &option_args {
+        match OptionKind::from_type(&arg.kind) {
+            Ok((_, false)) => optional = true,
+            Ok((_, true)) if optional => {
+                errors.push(Error::new(
+                    arg.name.span(),
+                    "required options must come before optional options",
+                ));
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    if !sub_commands.is_empty() && !option_args.is_empty() {
+        errors.push(Error::new(
+            option_args[0].name.span(),
+            "a command with sub commands cannot have options",
+        ));
+    }
+
+    // sub commands are also options.
+    if option_args.len() > MAX_OPTIONS {
+        errors.push(Error::new(
+            option_args[MAX_OPTIONS].name.span(),
+            format_args!("a command can have at most {} options", MAX_OPTIONS),
+        ));
+    }
+    if sub_commands.len() > MAX_OPTIONS {
+        errors.push(Error::new(
+            sub_commands[MAX_OPTIONS].span(),
+            format_args!("a command can have at most {} sub commands", MAX_OPTIONS),
+        ));
+    }
+
+    propagate_err!(errors.finish());
+
+    let fun_name = &fun.name;
+    let struct_name = fun.name.with_camel_suffix(APPLICATION_COMMAND);
+
+    let meta = command_meta(
+        &name,
+        description,
+        quote!(
+            serenity::model::interactions::application_command::ApplicationCommandType::ChatInput
+        ),
+        scope,
+        &options,
+    );
+    // errors about a sub command point to its name in `sub_commands(...)`.
+    let sub_commands = sub_commands
+        .iter()
+        .map(|sub_command| {
+            let mut struct_name = sub_command.with_camel_suffix(APPLICATION_COMMAND);
+            struct_name.set_span(sub_command.span());
+            struct_name
+        })
+        .collect::<Vec<_>>();
+    let model = command_model(
+        &name,
+        description,
+        "ChatInput",
+        command_options
+            .iter()
+            .map(CommandOption::model)
+            .chain(sub_commands.iter().map(|sub_command| {
+                quote!(<#sub_command as crate::app_cmd::ApplicationCommandTrait>::model().into_sub_command())
+            }))
+            .collect(),
+        &options,
+    );
+
+    let args = HandlerArgs::new("options");
+    let HandlerArgs {
+        ctx,
+        interaction,
+        options,
+        ..
+    } = &args;
+    let focused = Ident::new("focused", Span::mixed_site());
+    let partial = Ident::new("partial", Span::mixed_site());
+
+    let trait_path = quote!(crate::app_cmd::ApplicationCommandTrait);
+    let command_type_path =
+        quote!(serenity::model::interactions::application_command::ApplicationCommandType);
+
+    let call_args = args.call_args(&fun, command_options.iter().map(CommandOption::binding));
+    let option_extractors = command_options.iter().map(|option| {
+        let ident = option.binding();
+        let extractor = option.extractor(quote!(#options));
+
+        quote! {
+            let #ident = #extractor?;
+        }
+    });
+
+    // only override the default, which suggests nothing, if needed.
+    let autocomplete_arms = command_options
+        .iter()
+        .filter_map(|option| option.autocomplete_arm(ctx, interaction, &partial))
+        .collect::<Vec<_>>();
+    let autocomplete = if autocomplete_arms.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            async fn autocomplete(
+                #ctx: &serenity::client::Context,
+                #interaction: &serenity::model::interactions::autocomplete::AutocompleteInteraction,
+                #options: &[serenity::model::interactions::application_command::ApplicationCommandInteractionDataOption],
+            ) -> Vec<crate::app_cmd::AutocompleteChoice> {
+                let #focused = match #options.iter().find(|option| option.focused) {
+                    Some(option) => option,
+                    None => return Vec::new(),
+                };
+                let #partial = crate::app_cmd::partial_input(#focused);
+
+                match #focused.name.as_str() {
+                    #(#autocomplete_arms)*
+                    _ => Vec::new(),
+                }
+            }
+        }
+    };
+
+    // sub commands are dispatched by registry, with their path.
+    let sub_commands_const = if sub_commands.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            const SUB_COMMANDS: &'static [crate::app_cmd::ApplicationCommand] = &[
+                #(crate::app_cmd::ApplicationCommand::new::<#sub_commands>()),*
+            ];
+        }
+    };
+
+    let scope_assertions = sub_commands.iter().map(|sub_command| {
+        quote_spanned! {sub_command.span()=>
+            const _: () = assert!(
+                !<#sub_command as #trait_path>::SCOPED,
+                "sub commands are registered with their parent, so `#[only_in]` cannot be used",
+            );
+        }
+    });
+
+    let (depth, depth_assertion) = if sub_commands.is_empty() {
+        (quote!(0), quote!())
+    } else {
+        (
+            quote! {{
+                let mut depth = 0;
+                #(
+                    if <#sub_commands as #trait_path>::DEPTH > depth {
+                        depth = <#sub_commands as #trait_path>::DEPTH;
+                    }
+                )*
+                depth + 1
+            }},
+            quote! {
+                const _: () = assert!(
+                    <#struct_name as #trait_path>::DEPTH <= 2,
+                    "sub commands can be nested only two levels deep",
+                );
+                #(
+                    const _: () = assert!(
+                        matches!(
+                            <#sub_commands as #trait_path>::META.kind,
+                            #command_type_path::ChatInput
+                        ),
+                        "context menu commands cannot be sub commands",
+                    );
+                )*
+                #(#scope_assertions)*
+            },
+        )
+    };
+
+    let command = command_impl(
+        &fun,
+        meta,
+        model,
+        quote! {
+            const DEPTH: usize = #depth;
+            #sub_commands_const
+            #scoped
+            #autocomplete
+        },
+        &args,
+        quote! {
+            #(#option_extractors)*
+
+            #fun_name(#ctx, #interaction, #(#call_args),*).await
+        },
+    );
+
+    (quote! {
+        #command
+
+        #depth_assertion
+    })
+    .into()
+}
+
+#[proc_macro_attribute]
+pub fn app_check(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let fun = parse_macro_input!(input as CommandFun);
+
+    if !attr.is_empty() {
+        return Error::new(Span::call_site(), "`#[app_check]` takes no arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    let check = propagate_err!(CheckFun::new(fun));
+
+    (quote!(#check)).into()
+}
+
+#[proc_macro_attribute]
+pub fn app_component(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let fun = parse_macro_input!(input as CommandFun);
+    // prefix of `custom_id`. use function name if not set.
+    let prefix = if attr.is_empty() {
+        None
+    } else {
+        Some(parse_macro_input!(attr as Lit))
+    };
+
+    let component = propagate_err!(ComponentFun::new(fun, prefix));
+
+    (quote!(#component)).into()
+}
+
+#[proc_macro_attribute]
+pub fn user_command(attr: TokenStream, input: TokenStream) -> TokenStream {
+    context_menu_command(attr, input, "User", "target_user")
+}
+
+#[proc_macro_attribute]
+pub fn message_command(attr: TokenStream, input: TokenStream) -> TokenStream {
+    context_menu_command(attr, input, "Message", "target_message")
+}
+
+// right click menu command, `kind` is `User` or `Message`.
+// the function takes the target as `&User` or `&Message` instead of options,
+// got by `getter` in `crate::app_cmd`.
+fn context_menu_command(
+    attr: TokenStream,
+    input: TokenStream,
+    kind: &str,
+    getter: &str,
+) -> TokenStream {
+    // shown in the menu as is.
+    let CommandInput {
+        fun,
+        name,
+        options,
+        scope,
+        // report every attribute error together.
+        mut errors,
+        ..
+    } = propagate_err!(CommandInput::parse(attr, input, validate_context_menu_name));
+
+    // not sent to discord, only for logs.
+    let description = options.description.0.clone().unwrap_or_default();
+    let description = description.trim_end();
+
+    if let Some(sub_command) = options.sub_commands.first() {
+        errors.push(Error::new(
+            sub_command.span(),
+            "context menu commands cannot have sub commands",
+        ));
+    }
+
+    let target_args = fun.args[2..]
+        .iter()
+        .filter(|arg| !is_responder(&arg.kind))
+        .collect::<Vec<_>>();
+    if target_args.len() != 1 {
+        errors.push(Error::new(
+            fun.name.span(),
+            format_args!(
+                "context menu commands take exactly one target argument, `&{}`",
+                kind
+            ),
+        ));
+    }
+
+    propagate_err!(errors.finish());
+
+    let fun_name = &fun.name;
+    let getter = Ident::new(getter, Span::call_site());
+
+    let kind_ident = Ident::new(kind, Span::call_site());
+    let meta = command_meta(
+        &name,
+        description,
+        quote!(serenity::model::interactions::application_command::ApplicationCommandType::#kind_ident),
+        scope,
+        &options,
+    );
+    // context menu commands have no description and options.
+    let model = command_model(&name, "", kind, Vec::new(), &options);
+
+    let args = HandlerArgs::new("_options");
+    let HandlerArgs {
+        ctx, interaction, ..
+    } = &args;
+    let target = Ident::new("target", Span::mixed_site());
+    let call_args = args.call_args(&fun, std::iter::repeat(target.clone()));
+
+    command_impl(
+        &fun,
+        meta,
+        model,
+        quote!(),
+        &args,
+        quote! {
+            let #target = crate::app_cmd::#getter(#interaction)?;
 
-    attr
+            #fun_name(#ctx, #interaction, #(#call_args),*).await
+        },
+    )
+    .into()
 }
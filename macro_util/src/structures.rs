@@ -10,12 +10,16 @@ use syn::{
     parse::{Error, Parse, ParseStream, Result},
     punctuated::Punctuated,
     spanned::Spanned,
-    Attribute, Block, Expr, ExprClosure, FnArg, Ident, Pat, ReturnType, Stmt, Token, Type,
-    Visibility,
+    Attribute, Block, Expr, ExprClosure, FnArg, GenericArgument, Ident, Lit, NestedMeta, Pat,
+    PathArguments, ReturnType, Stmt, Token, Type, Visibility,
 };
 
-use crate::consts::CHECK;
-use crate::util::{rename_attributes, Argument, AsOption, IdentExt2, Parenthesised};
+use crate::attributes::{parse, parse_meta_values, parse_values, Values};
+use crate::consts::{CHECK, COMPONENT, MAX_CHOICES, MAX_CUSTOM_ID_LENGTH};
+use crate::util::{
+    append_line, rename_attributes, validate_description, validate_name, Argument, AsOption,
+    Errors, IdentExt2, LitExt, Parenthesised,
+};
 
 // check, is this a other attribute
 fn is_cooked(attr: &Attribute) -> bool {
@@ -32,6 +36,7 @@ fn parse_argument(arg: FnArg) -> Result<Argument> {
         // typed argument,
         // `#muutable #name: #kind`
         FnArg::Typed(typed) => {
+            let attributes = typed.attrs;
             let pat = typed.pat;
             let kind = typed.ty;
 
@@ -43,6 +48,7 @@ fn parse_argument(arg: FnArg) -> Result<Argument> {
                     let mutable = id.mutability;
 
                     Ok(Argument {
+                        attributes,
                         mutable,
                         name,
                         kind: *kind, // maybe remove ref?
@@ -59,6 +65,7 @@ fn parse_argument(arg: FnArg) -> Result<Argument> {
                     let name = Ident::new("_", token.spans[0]);
 
                     Ok(Argument {
+                        attributes,
                         mutable: None, // not use -> shoud not/ must not mutable
                         name,
                         kind: *kind, // ^ see before match arm.
@@ -134,9 +141,6 @@ impl Parse for CommandFun {
         // inculude before fn keyword, [#hoge]'s vector
         let mut attributes = input.call(Attribute::parse_outer)?;
 
-        //TODO: remove
-        dbg!(&attributes);
-
         // doc rename to description
         rename_attributes(&mut attributes, "doc", "description");
 
@@ -193,6 +197,165 @@ impl Parse for CommandFun {
     }
 }
 
+impl ToTokens for CommandFun {
+    fn to_tokens(&self, stream: &mut TokenStream2) {
+        let Self {
+            attributes: _,
+            cooked,
+            visibility,
+            name,
+            args,
+            ret,
+            body,
+        } = self;
+
+        // re-emit the annotated function as is, without our attributes
+        stream.extend(quote! {
+            #(#cooked)*
+            #visibility async fn #name (#(#args),*) -> #ret {
+                #(#body)*
+            }
+        });
+    }
+}
+
+// `#[app_check]` function, generate `<NAME>_CHECK` static.
+#[derive(Debug)]
+pub struct CheckFun(CommandFun);
+
+impl CheckFun {
+    pub fn new(fun: CommandFun) -> Result<Self> {
+        // report every error together.
+        let mut errors = Errors::default();
+
+        // doc comments are allowed, but not used.
+        for attr in fun
+            .attributes
+            .iter()
+            .filter(|attr| !attr.path.is_ident("description"))
+        {
+            errors.push(Error::new(attr.span(), "checks take no attributes"));
+        }
+
+        if fun.args.len() != 2 {
+            errors.push(Error::new(
+                fun.name.span(),
+                "the check must take `&Context` and `&ApplicationCommandInteraction`",
+            ));
+        }
+
+        errors.finish()?;
+
+        Ok(Self(fun))
+    }
+}
+
+impl ToTokens for CheckFun {
+    fn to_tokens(&self, stream: &mut TokenStream2) {
+        let fun = &self.0;
+        let visibility = &fun.visibility;
+        let fun_name = &fun.name;
+        let name = fun.name.to_string_non_raw();
+        let static_name = fun.name.with_suffix(CHECK);
+
+        stream.extend(quote! {
+            #visibility static #static_name: crate::app_cmd::Check = crate::app_cmd::Check {
+                name: #name,
+                function: |ctx, interaction| Box::pin(#fun_name(ctx, interaction)),
+            };
+
+            #fun
+        });
+    }
+}
+
+// `#[app_component]` function, generate `<NAME>_COMPONENT` static.
+#[derive(Debug)]
+pub struct ComponentFun {
+    fun: CommandFun,
+    prefix: String,
+}
+
+impl ComponentFun {
+    pub fn new(fun: CommandFun, prefix: Option<Lit>) -> Result<Self> {
+        // report every error together.
+        let mut errors = Errors::default();
+
+        for attr in fun
+            .attributes
+            .iter()
+            .filter(|attr| !attr.path.is_ident("description"))
+        {
+            errors.push(Error::new(attr.span(), "components take no attributes"));
+        }
+
+        if !(2..=3).contains(&fun.args.len()) {
+            errors.push(Error::new(
+                fun.name.span(),
+                "the component must take `&Context`, `&MessageComponentInteraction` and optional state",
+            ));
+        }
+
+        let (prefix, span) = match prefix {
+            Some(lit) => (collect_err!(errors, lit.to_str()), lit.span()),
+            None => (Some(fun.name.to_string_non_raw()), fun.name.span()),
+        };
+        // `<prefix>:<owner>:<state>`, owner is at most 20 digits.
+        if let Some(prefix) = &prefix {
+            if prefix.is_empty() || prefix.contains(':') || prefix.len() > MAX_CUSTOM_ID_LENGTH - 22
+            {
+                errors.push(Error::new(
+                    span,
+                    "component prefix must be short, non-empty and must not contain `:`",
+                ));
+            }
+        }
+
+        errors.finish()?;
+        let prefix = prefix.unwrap_or_default();
+
+        Ok(Self { fun, prefix })
+    }
+}
+
+impl ToTokens for ComponentFun {
+    fn to_tokens(&self, stream: &mut TokenStream2) {
+        let fun = &self.fun;
+        let visibility = &fun.visibility;
+        let fun_name = &fun.name;
+        let prefix = &self.prefix;
+        let static_name = fun.name.with_suffix(COMPONENT);
+
+        // `&str` state is passed as is, other types are parsed.
+        let function = match fun.args.get(2).map(|arg| &arg.kind) {
+            None => quote!(|ctx, component, _| Box::pin(#fun_name(ctx, component))),
+            Some(Type::Reference(_)) => {
+                quote!(|ctx, component, state| Box::pin(#fun_name(ctx, component, state)))
+            }
+            Some(kind) => quote! {
+                |ctx, component, state| Box::pin(async move {
+                    let state = crate::app_cmd::parse_state::<#kind>(state)?;
+                    #fun_name(ctx, component, state).await
+                })
+            },
+        };
+
+        stream.extend(quote! {
+            #visibility static #static_name: crate::app_cmd::Component = crate::app_cmd::Component {
+                prefix: #prefix,
+                function: #function,
+            };
+
+            // collected by `crate::app_cmd::components`.
+            ::inventory::submit! {
+                &#static_name
+            }
+
+            #fun
+        });
+    }
+}
+
 // Ident vector, has any check???
 // TODO
 #[derive(Debug, Default)]
@@ -210,13 +373,74 @@ impl ToTokens for Checks {
 #[derive(Debug, Default)]
 pub struct Permissions(pub u64);
 
+// same as serenity's `Permissions` flags.
+const PERMISSIONS: &[(&str, u64)] = &[
+    ("CREATE_INVITE", 1 << 0),
+    ("KICK_MEMBERS", 1 << 1),
+    ("BAN_MEMBERS", 1 << 2),
+    ("ADMINISTRATOR", 1 << 3),
+    ("MANAGE_CHANNELS", 1 << 4),
+    ("MANAGE_GUILD", 1 << 5),
+    ("ADD_REACTIONS", 1 << 6),
+    ("VIEW_AUDIT_LOG", 1 << 7),
+    ("PRIORITY_SPEAKER", 1 << 8),
+    ("STREAM", 1 << 9),
+    ("READ_MESSAGES", 1 << 10),
+    ("SEND_MESSAGES", 1 << 11),
+    ("SEND_TTS_MESSAGES", 1 << 12),
+    ("MANAGE_MESSAGES", 1 << 13),
+    ("EMBED_LINKS", 1 << 14),
+    ("ATTACH_FILES", 1 << 15),
+    ("READ_MESSAGE_HISTORY", 1 << 16),
+    ("MENTION_EVERYONE", 1 << 17),
+    ("USE_EXTERNAL_EMOJIS", 1 << 18),
+    ("VIEW_GUILD_INSIGHTS", 1 << 19),
+    ("CONNECT", 1 << 20),
+    ("SPEAK", 1 << 21),
+    ("MUTE_MEMBERS", 1 << 22),
+    ("DEAFEN_MEMBERS", 1 << 23),
+    ("MOVE_MEMBERS", 1 << 24),
+    ("USE_VAD", 1 << 25),
+    ("CHANGE_NICKNAME", 1 << 26),
+    ("MANAGE_NICKNAMES", 1 << 27),
+    ("MANAGE_ROLES", 1 << 28),
+    ("MANAGE_WEBHOOKS", 1 << 29),
+    ("MANAGE_EMOJIS", 1 << 30),
+    ("USE_SLASH_COMMANDS", 1 << 31),
+    ("REQUEST_TO_SPEAK", 1 << 32),
+    ("MANAGE_THREADS", 1 << 34),
+    ("CREATE_PUBLIC_THREADS", 1 << 35),
+    ("CREATE_PRIVATE_THREADS", 1 << 36),
+    ("USE_EXTERNAL_STICKERS", 1 << 37),
+    ("SEND_MESSAGES_IN_THREADS", 1 << 38),
+    ("START_EMBEDDED_ACTIVITIES", 1 << 39),
+    ("MODERATE_MEMBERS", 1 << 40),
+    // deprecated in serenity, but still accepted there. same bits as serenity.
+    (
+        "USE_PUBLIC_THREADS",
+        0b0010_0000_0000_0000_0000_0000_0000_0000_0000,
+    ),
+    (
+        "USE_PRIVATE_THREADS",
+        0b0100_0000_0000_0000_0000_0000_0000_0000_0000,
+    ),
+    ("PRESET_GENERAL", 0b0000_0110_0011_0111_1101_1100_0100_0001),
+    ("PRESET_TEXT", 0b0000_0100_0000_0111_1101_1100_0100_0001),
+    ("PRESET_VOICE", 0b0000_0010_0011_0000_0000_0000_0000_0000),
+];
+
 impl Permissions {
-    #[allow(unreachable_code)] //TODO
+    // accept `NAME` or `NAME | NAME | ...`
     pub fn from_str(s: &str) -> Option<Self> {
-        Some(Permissions(match s.to_uppercase().as_str() {
-            //            "PRESET_GENERAL" => 0b0000_0110_0011_0111_1101_1100_0100_0001,
-            _ => return None,
-        }))
+        let mut bits = 0;
+
+        for name in s.split('|') {
+            let name = name.trim().to_uppercase();
+            let (_, p) = PERMISSIONS.iter().find(|(n, _)| *n == name)?;
+            bits |= p;
+        }
+
+        Some(Permissions(bits))
     }
 }
 
@@ -238,41 +462,30 @@ impl ToTokens for Permissions {
 pub enum OnlyIn {
     Global,
     Guild(u64),
+    // the guild in `GUILD_ID`.
+    Dev,
     None,
 }
 
 impl OnlyIn {
     #[inline]
     pub fn from_str(s: &str, span: Span) -> Result<Self> {
-        if s.len() < 6 {
-            return Err(Error::new(span, "invailid restriction type"));
+        if s == "global" {
+            return Ok(OnlyIn::Global);
         }
-        match &s[0..5] {
-            "global" => Ok(OnlyIn::Global),
-            "guild(" => {
-                if !s.ends_with(")") {
-                    return Err(Error::new(span, "invailid restriction type (must end `)`)"));
-                };
-                let i = s.strip_prefix("guild(").unwrap().strip_suffix(")").unwrap();
-                Ok(OnlyIn::Guild(match i.parse::<u64>() {
-                    Ok(i) => i,
-                    _ => return Err(Error::new(span, "invailid restriction type (must u64)")),
-                }))
-            }
-            _ => return Err(Error::new(span, "invailid restriction type")),
+        if s == "dev" {
+            return Ok(OnlyIn::Dev);
         }
-    }
-}
 
-impl ToTokens for OnlyIn {
-    fn to_tokens(&self, stream: &mut TokenStream2) {
-        // TODO: replace it
-        //        let path = quote!(crate::);
-        let only_in_path = quote!(serenity::framework::standard::OnlyIn);
-        match self {
-            OnlyIn::Global => stream.extend(quote!(#only_in_path::Global)),
-            OnlyIn::Guild(i) => stream.extend(quote!(#only_in_path::Guild(#i))),
-            OnlyIn::None => stream.extend(quote!(#only_in_path::None)),
+        match s.strip_prefix("guild(") {
+            Some(rest) => match rest.strip_suffix(')') {
+                Some(i) => match i.parse::<u64>() {
+                    Ok(i) => Ok(OnlyIn::Guild(i)),
+                    _ => Err(Error::new(span, "invailid restriction type (must u64)")),
+                },
+                None => Err(Error::new(span, "invailid restriction type (must end `)`)")),
+            },
+            None => Err(Error::new(span, "invailid restriction type")),
         }
     }
 }
@@ -284,30 +497,617 @@ impl Default for OnlyIn {
     }
 }
 
+// `CommandScope` from `#[only_in(...)]`. dev guild only if not set.
+pub fn command_scope(only_in: &[OnlyIn], span: Span) -> Result<TokenStream2> {
+    let path = quote!(crate::app_cmd::CommandScope);
+
+    let guilds = only_in
+        .iter()
+        .filter_map(|only_in| match only_in {
+            OnlyIn::Guild(id) => Some(*id),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let global = only_in.contains(&OnlyIn::Global);
+    let dev = only_in.contains(&OnlyIn::Dev);
+
+    match (global, dev, guilds.is_empty()) {
+        (true, false, true) => Ok(quote!(#path::Global)),
+        (false, false, false) => Ok(quote! {
+            #path::Guilds(&[#(serenity::model::id::GuildId(#guilds)),*])
+        }),
+        (false, _, true) => Ok(quote!(#path::Dev)),
+        _ => Err(Error::new(
+            span,
+            "`global` and `dev` cannot be combined with other scopes",
+        )),
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct Options {
     pub checks: Checks,
-    pub bucket: AsOption<String>,
-    pub aliases: Vec<String>,
     pub description: AsOption<String>,
-    pub delimiters: Vec<String>,
-    pub usage: AsOption<String>,
-    pub examples: Vec<String>,
-    pub allowed_roles: Vec<String>,
     pub required_permissions: Permissions,
-    pub help_available: bool,
     pub only_in: Vec<OnlyIn>,
-    pub owners_only: bool,
-    pub owner_privilege: bool,
     pub sub_commands: Vec<Ident>,
+    // seconds between uses per user.
+    pub cooldown: AsOption<u64>,
+    // reply only to the invoking user by default.
+    pub ephemeral: bool,
+    // slow command, so acknowledge before running it.
+    pub defer: bool,
 }
 
-impl Options {
-    #[inline]
-    pub fn new() -> Self {
-        Self {
-            help_available: true,
-            ..Default::default()
+// slash command option type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OptionKind {
+    String,
+    Integer,
+    Boolean,
+    User,
+    Channel,
+    Role,
+    Number,
+}
+
+impl OptionKind {
+    // map argument type to option type.
+    // `Option<T>` mean not required option, so return `(kind, required)`.
+    pub fn from_type(ty: &Type) -> Result<(Self, bool)> {
+        let segment = match ty {
+            Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+            _ => None,
+        };
+
+        let segment = match segment {
+            Some(segment) => segment,
+            None => return Err(Error::new(ty.span(), "unsupported option type")),
+        };
+
+        if segment.ident == "Option" {
+            let inner = match &segment.arguments {
+                PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+                    match &args.args[0] {
+                        GenericArgument::Type(inner) => inner,
+                        _ => return Err(Error::new(ty.span(), "expected `Option<T>`")),
+                    }
+                }
+                _ => return Err(Error::new(ty.span(), "expected `Option<T>`")),
+            };
+
+            return match Self::from_type(inner)? {
+                (kind, true) => Ok((kind, false)),
+                (_, false) => Err(Error::new(
+                    ty.span(),
+                    "`Option<Option<T>>` is not supported",
+                )),
+            };
         }
+
+        let kind = match segment.ident.to_string().as_str() {
+            "String" => OptionKind::String,
+            "i64" => OptionKind::Integer,
+            "bool" => OptionKind::Boolean,
+            "User" | "UserId" => OptionKind::User,
+            "PartialChannel" | "ChannelId" => OptionKind::Channel,
+            "Role" | "RoleId" => OptionKind::Role,
+            "f64" => OptionKind::Number,
+            _ => {
+                return Err(Error::new(
+                    ty.span(),
+                    "unsupported option type: expected one of `String`, `i64`, `f64`, `bool`, \
+                     `User`, `UserId`, `PartialChannel`, `ChannelId`, `Role`, `RoleId` or `Option<T>`",
+                ))
+            }
+        };
+
+        Ok((kind, true))
+    }
+}
+
+impl ToTokens for OptionKind {
+    fn to_tokens(&self, stream: &mut TokenStream2) {
+        let path = quote!(crate::app_cmd_model::OptionKind);
+
+        stream.extend(match self {
+            OptionKind::String => quote!(#path::String),
+            OptionKind::Integer => quote!(#path::Integer),
+            OptionKind::Boolean => quote!(#path::Boolean),
+            OptionKind::User => quote!(#path::User),
+            OptionKind::Channel => quote!(#path::Channel),
+            OptionKind::Role => quote!(#path::Role),
+            OptionKind::Number => quote!(#path::Number),
+        });
+    }
+}
+
+// `#[choice("<name>", <value>)]`, value is checked with option type later.
+#[derive(Debug)]
+pub struct Choice {
+    pub name: String,
+    pub value: Lit,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LitValue {
+    String(String),
+    Integer(i64),
+    Number(f64),
+}
+
+impl LitValue {
+    pub fn new(lit: &Lit, kind: OptionKind) -> Result<Self> {
+        let value = match (kind, lit) {
+            (OptionKind::String, Lit::Str(s)) => LitValue::String(s.value()),
+            (OptionKind::Integer, Lit::Int(i)) => {
+                let i = i.base10_parse::<i32>()?;
+                LitValue::Integer(i as i64)
+            }
+            (OptionKind::Number, Lit::Int(i)) => LitValue::Number(i.base10_parse()?),
+            (OptionKind::Number, Lit::Float(f)) => LitValue::Number(f.base10_parse()?),
+            (OptionKind::String, _) => {
+                return Err(Error::new(
+                    lit.span(),
+                    "choice value must be a string literal",
+                ))
+            }
+            (OptionKind::Integer, _) => {
+                return Err(Error::new(
+                    lit.span(),
+                    "choice value must be an integer literal",
+                ))
+            }
+            (OptionKind::Number, _) => {
+                return Err(Error::new(
+                    lit.span(),
+                    "choice value must be a number literal",
+                ))
+            }
+            _ => {
+                return Err(Error::new(
+                    lit.span(),
+                    "choices are only allowed on `String`, `i64` and `f64` options",
+                ))
+            }
+        };
+
+        Ok(value)
+    }
+
+    pub fn new_bound(bound: &Bound, kind: OptionKind) -> Result<Self> {
+        let Bound { negative, lit } = bound;
+
+        let value = match (kind, lit) {
+            (OptionKind::Integer, Lit::Int(i)) => {
+                let i = i.base10_parse::<i64>()?;
+                let i = if *negative { -i } else { i };
+
+                if i32::try_from(i).is_err() {
+                    return Err(Error::new(lit.span(), "integer bound must fit in `i32`"));
+                }
+                LitValue::Integer(i)
+            }
+            (OptionKind::Integer, Lit::Float(_)) => {
+                return Err(Error::new(
+                    lit.span(),
+                    "float bound is not allowed on an integer option",
+                ))
+            }
+            (OptionKind::Number, Lit::Int(i)) => {
+                let n = i.base10_parse::<f64>()?;
+                LitValue::Number(if *negative { -n } else { n })
+            }
+            (OptionKind::Number, Lit::Float(f)) => {
+                let n = f.base10_parse::<f64>()?;
+                LitValue::Number(if *negative { -n } else { n })
+            }
+            (OptionKind::Integer, _) | (OptionKind::Number, _) => {
+                return Err(Error::new(lit.span(), "bound must be a number literal"))
+            }
+            _ => {
+                return Err(Error::new(
+                    lit.span(),
+                    "min/max are only allowed on `i64` and `f64` options",
+                ))
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn greater_than(&self, other: &Self) -> bool {
+        match (self, other) {
+            (LitValue::Integer(a), LitValue::Integer(b)) => a > b,
+            (LitValue::Number(a), LitValue::Number(b)) => a > b,
+            _ => false,
+        }
+    }
+}
+
+// `#[min = <value>]` or `#[max = <value>]`.
+// negative number is not literal, so cannot parse as `Meta`.
+#[derive(Debug)]
+pub struct Bound {
+    pub negative: bool,
+    pub lit: Lit,
+}
+
+impl Parse for Bound {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        input.parse::<Token![=]>()?;
+        let negative = input.parse::<Option<Token![-]>>()?.is_some();
+        let lit = input.parse::<Lit>()?;
+
+        Ok(Bound { negative, lit })
+    }
+}
+
+// `#[autocomplete = <function path>]`.
+// path is not literal, so cannot parse as `Meta`.
+#[derive(Debug)]
+pub struct AutocompleteFn(pub syn::Path);
+
+impl Parse for AutocompleteFn {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        input.parse::<Token![=]>()?;
+
+        Ok(AutocompleteFn(input.parse()?))
+    }
+}
+
+impl ToTokens for LitValue {
+    fn to_tokens(&self, stream: &mut TokenStream2) {
+        match self {
+            LitValue::String(s) => s.to_tokens(stream),
+            LitValue::Integer(i) => i.to_tokens(stream),
+            LitValue::Number(n) => n.to_tokens(stream),
+        }
+    }
+}
+
+// `#[application_command(<name>, <meta>, ...)]` arguments.
+#[derive(Debug, Default)]
+pub struct CommandArgs {
+    pub name: Option<Lit>,
+    pub values: Vec<Values>,
+    // invalid arguments, reported with the other errors of the command.
+    pub errors: Errors,
+}
+
+impl Parse for CommandArgs {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let nested = Punctuated::<NestedMeta, Token![,]>::parse_terminated(input)?;
+
+        let mut args = CommandArgs::default();
+
+        for meta in nested {
+            match meta {
+                NestedMeta::Lit(lit) => {
+                    if args.name.is_some() {
+                        args.errors
+                            .push(Error::new(lit.span(), "command name is already set"));
+                        continue;
+                    }
+                    args.name = Some(lit);
+                }
+                NestedMeta::Meta(meta) => {
+                    let span = meta.span();
+                    if let Some(values) = collect_err!(args.errors, parse_meta_values(meta, span)) {
+                        args.values.push(values);
+                    }
+                }
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+// command option, generated from a function argument.
+#[derive(Debug)]
+pub struct CommandOption {
+    // option name, from argument name
+    pub name: String,
+    // argument type
+    pub ty: Type,
+    pub kind: OptionKind,
+    pub required: bool,
+    pub description: AsOption<String>,
+    pub choices: Vec<(String, LitValue)>,
+    pub min: AsOption<LitValue>,
+    pub max: AsOption<LitValue>,
+    // function suggests values while typing.
+    pub autocomplete: Option<syn::Path>,
+}
+
+impl CommandOption {
+    pub fn new(arg: &Argument) -> Result<Self> {
+        if arg.name == "_" {
+            return Err(Error::new(
+                arg.name.span(),
+                "option argument must have a name",
+            ));
+        }
+
+        let (kind, required) = OptionKind::from_type(&arg.kind)?;
+
+        let mut option = Self {
+            name: arg.name.to_string_non_raw(),
+            ty: arg.kind.clone(),
+            kind,
+            required,
+            description: AsOption::default(),
+            choices: Vec::new(),
+            min: AsOption::default(),
+            max: AsOption::default(),
+            autocomplete: None,
+        };
+
+        // report every attribute error together.
+        let mut errors = Errors::default();
+
+        for attribute in &arg.attributes {
+            collect_err!(errors, option.parse_attribute(attribute));
+        }
+
+        collect_err!(errors, validate_name(&option.name, arg.name.span()));
+
+        if option.autocomplete.is_some() && !option.choices.is_empty() {
+            errors.push(Error::new(
+                arg.name.span(),
+                "an option cannot have both choices and autocomplete",
+            ));
+        }
+
+        match option.description.0.as_deref() {
+            Some(description) => {
+                collect_err!(
+                    errors,
+                    validate_description(description.trim_end(), arg.name.span())
+                );
+            }
+            None => errors.push(Error::new(
+                arg.name.span(),
+                "option must have a description: `#[description = \"...\"]`",
+            )),
+        }
+
+        errors.finish()?;
+
+        Ok(option)
+    }
+
+    fn parse_attribute(&mut self, attribute: &Attribute) -> Result<()> {
+        if attribute.path.is_ident("min") || attribute.path.is_ident("max") {
+            let bound = syn::parse2::<Bound>(attribute.tokens.clone())?;
+            let value = LitValue::new_bound(&bound, self.kind)?;
+
+            if attribute.path.is_ident("min") {
+                self.min = AsOption(Some(value));
+            } else {
+                self.max = AsOption(Some(value));
+            }
+
+            if let (Some(min), Some(max)) = (&self.min.0, &self.max.0) {
+                if min.greater_than(max) {
+                    return Err(Error::new(
+                        attribute.span(),
+                        "`min` must not be greater than `max`",
+                    ));
+                }
+            }
+
+            return Ok(());
+        }
+
+        if attribute.path.is_ident("autocomplete") {
+            if !matches!(
+                self.kind,
+                OptionKind::String | OptionKind::Integer | OptionKind::Number
+            ) {
+                return Err(Error::new(
+                    attribute.span(),
+                    "autocomplete is only allowed on `String`, `i64` and `f64` options",
+                ));
+            }
+
+            let AutocompleteFn(path) = syn::parse2(attribute.tokens.clone())?;
+            self.autocomplete = Some(path);
+
+            return Ok(());
+        }
+
+        let values = parse_values(attribute)?;
+
+        match values.name.to_string().as_str() {
+            "description" => {
+                let line: String = parse(values)?;
+                append_line(&mut self.description, line);
+            }
+            "choice" => {
+                let choice: Choice = parse(values)?;
+                let value = LitValue::new(&choice.value, self.kind)?;
+
+                if self.choices.iter().any(|(_, v)| *v == value) {
+                    return Err(Error::new(choice.span, "duplicate choice value"));
+                }
+                if self.choices.len() == MAX_CHOICES {
+                    return Err(Error::new(
+                        choice.span,
+                        format_args!("an option can have at most {} choices", MAX_CHOICES),
+                    ));
+                }
+
+                self.choices.push((choice.name, value));
+            }
+            name => {
+                return Err(Error::new(
+                    attribute.span(),
+                    format_args!("invalid option attribute: {:?}", name),
+                ))
+            }
+        }
+
+        Ok(())
+    }
+
+    // local variable for extracted value.
+    // mixed site span, so never shadow the command function itself.
+    pub fn binding(&self) -> Ident {
+        Ident::new(&format!("option_{}", self.name), Span::mixed_site())
+    }
+
+    // expression to get this option's value from `options` slice.
+    pub fn extractor(&self, options: TokenStream2) -> TokenStream2 {
+        let Self {
+            name,
+            ty,
+            choices,
+            min,
+            max,
+            ..
+        } = self;
+
+        let mut extractor = quote!(crate::app_cmd::parse_option::<#ty>(#options, #name));
+
+        if !choices.is_empty() {
+            let values = choices.iter().map(|(_, value)| value);
+
+            extractor = quote! {
+                #extractor.and_then(|value| {
+                    crate::app_cmd::check_choice(value, #name, &[#(&#values),*])
+                })
+            };
+        }
+
+        if min.0.is_some() || max.0.is_some() {
+            extractor = quote! {
+                #extractor.and_then(|value| {
+                    crate::app_cmd::check_range(value, #name, #min, #max)
+                })
+            };
+        }
+
+        extractor
+    }
+
+    // match arm to call autocomplete function, if this option has it.
+    pub fn autocomplete_arm(
+        &self,
+        ctx: &Ident,
+        interaction: &Ident,
+        partial: &Ident,
+    ) -> Option<TokenStream2> {
+        let name = &self.name;
+        let path = self.autocomplete.as_ref()?;
+
+        Some(quote! {
+            #name => #path(#ctx, #interaction, &#partial)
+                .await
+                .into_iter()
+                .map(Into::into)
+                .take(crate::app_cmd::MAX_AUTOCOMPLETE_CHOICES)
+                .collect(),
+        })
+    }
+}
+
+impl CommandOption {
+    // `crate::app_cmd_model::CommandOption` of this option.
+    pub fn model(&self) -> TokenStream2 {
+        let path = quote!(crate::app_cmd_model);
+        let Self {
+            name,
+            kind,
+            required,
+            description,
+            choices,
+            min,
+            max,
+            autocomplete,
+            ..
+        } = self;
+        let description = description.0.as_deref().unwrap_or_default().trim_end();
+        let autocomplete = autocomplete.is_some();
+
+        let bound = |bound: &AsOption<LitValue>| match &bound.0 {
+            Some(LitValue::Integer(i)) => quote!(Some(#path::BoundValue::Integer(#i))),
+            Some(LitValue::Number(n)) => quote!(Some(#path::BoundValue::Number(#n))),
+            _ => quote!(None),
+        };
+        let min = bound(min);
+        let max = bound(max);
+
+        let choices = choices.iter().map(|(name, value)| {
+            let value = match value {
+                LitValue::String(s) => quote!(#path::ChoiceValue::String(#s.to_string())),
+                LitValue::Integer(i) => quote!(#path::ChoiceValue::Integer(#i)),
+                LitValue::Number(n) => quote!(#path::ChoiceValue::Number(#n)),
+            };
+            quote! {
+                #path::Choice {
+                    name: #name.to_string(),
+                    value: #value,
+                }
+            }
+        });
+
+        quote! {
+            #path::CommandOption {
+                kind: #kind,
+                name: #name.to_string(),
+                description: #description.to_string(),
+                required: #required,
+                choices: vec![#(#choices),*],
+                min_value: #min,
+                max_value: #max,
+                autocomplete: #autocomplete,
+                options: Vec::new(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permissions_from_str() {
+        assert_eq!(Permissions::from_str("ADMINISTRATOR").unwrap().0, 1 << 3);
+        assert_eq!(Permissions::from_str("kick_members").unwrap().0, 1 << 1);
+        assert_eq!(
+            Permissions::from_str("KICK_MEMBERS | BAN_MEMBERS")
+                .unwrap()
+                .0,
+            1 << 1 | 1 << 2
+        );
+        assert_eq!(
+            Permissions::from_str("USE_PUBLIC_THREADS").unwrap().0,
+            1 << 33
+        );
+        assert_eq!(
+            Permissions::from_str("USE_PRIVATE_THREADS").unwrap().0,
+            1 << 34
+        );
+        assert!(Permissions::from_str("KICK").is_none());
+        assert!(Permissions::from_str("KICK_MEMBERS |").is_none());
+        assert!(Permissions::from_str("").is_none());
+    }
+
+    #[test]
+    fn only_in_from_str() {
+        let span = Span::call_site();
+        assert_eq!(OnlyIn::from_str("global", span).unwrap(), OnlyIn::Global);
+        assert_eq!(OnlyIn::from_str("dev", span).unwrap(), OnlyIn::Dev);
+        assert_eq!(
+            OnlyIn::from_str("guild(123)", span).unwrap(),
+            OnlyIn::Guild(123)
+        );
+        assert!(OnlyIn::from_str("guild(abc)", span).is_err());
+        assert!(OnlyIn::from_str("guild(123", span).is_err());
+        assert!(OnlyIn::from_str("dm", span).is_err());
     }
 }
@@ -5,10 +5,14 @@ use syn::{
     parenthesized,
     parse::{Error, Parse, ParseStream, Result as SynResult},
     punctuated::Punctuated,
+    spanned::Spanned,
     token::{Comma, Mut},
     Attribute, Ident, Lit, Path, PathSegment, Type,
 };
 
+use crate::consts::{MAX_DESCRIPTION_LENGTH, MAX_NAME_LENGTH};
+use crate::structures::CommandFun;
+
 // line 275
 pub fn rename_attributes(attributes: &mut Vec<Attribute>, name: &str, target: &str) {
     for attr in attributes {
@@ -33,6 +37,8 @@ impl<T: Parse> Parse for Parenthesised<T> {
 
 #[derive(Debug)] // line 157
 pub struct Argument {
+    // parameter attributes, e.g. `#[description = "..."]`
+    pub attributes: Vec<Attribute>,
     pub mutable: Option<Mut>,
     pub name: Ident,
     pub kind: Type,
@@ -41,6 +47,7 @@ pub struct Argument {
 impl ToTokens for Argument {
     fn to_tokens(&self, stream: &mut TokenStream2) {
         let Argument {
+            attributes: _,
             mutable,
             name,
             kind,
@@ -54,35 +61,51 @@ impl ToTokens for Argument {
 
 // line 25
 pub trait LitExt {
-    fn to_str(&self) -> String;
-    fn to_bool(&self) -> bool;
-    fn to_ident(&self) -> Ident;
+    fn to_str(&self) -> SynResult<String>;
+    fn to_bool(&self) -> SynResult<bool>;
+    fn to_ident(&self) -> SynResult<Ident>;
+    fn to_u64(&self) -> SynResult<u64>;
 }
 
 impl LitExt for Lit {
-    fn to_str(&self) -> String {
+    fn to_str(&self) -> SynResult<String> {
         match self {
-            Lit::Str(s) => s.value(),
-            Lit::ByteStr(s) => unsafe { String::from_utf8_unchecked(s.value()) },
-            Lit::Char(c) => c.value().to_string(),
-            Lit::Byte(b) => (b.value() as char).to_string(),
-            _ => panic!("values must be a (byte)string or a char"),
+            Lit::Str(s) => Ok(s.value()),
+            Lit::ByteStr(s) => String::from_utf8(s.value())
+                .map_err(|_| Error::new(self.span(), "byte string must be valid utf-8")),
+            Lit::Char(c) => Ok(c.value().to_string()),
+            Lit::Byte(b) => Ok((b.value() as char).to_string()),
+            _ => Err(Error::new(
+                self.span(),
+                "values must be a (byte)string or a char",
+            )),
         }
     }
 
-    fn to_bool(&self) -> bool {
+    fn to_bool(&self) -> SynResult<bool> {
         if let Lit::Bool(b) = self {
-            b.value
-        } else {
-            self.to_str()
-                .parse()
-                .unwrap_or_else(|_| panic!("expected bool from {:?}", self))
+            return Ok(b.value);
         }
+
+        self.to_str()?
+            .parse()
+            .map_err(|_| Error::new(self.span(), "expected `true` or `false`"))
     }
 
     #[inline]
-    fn to_ident(&self) -> Ident {
-        Ident::new(&self.to_str(), self.span())
+    fn to_ident(&self) -> SynResult<Ident> {
+        let mut ident = syn::parse_str::<Ident>(&self.to_str()?)
+            .map_err(|_| Error::new(self.span(), "expected an identifier"))?;
+        ident.set_span(self.span());
+
+        Ok(ident)
+    }
+
+    fn to_u64(&self) -> SynResult<u64> {
+        match self {
+            Lit::Int(i) => i.base10_parse(),
+            _ => Err(Error::new(self.span(), "expected an unsigned integer")),
+        }
     }
 }
 
@@ -91,6 +114,8 @@ pub trait IdentExt2: Sized {
     fn to_string_non_raw(&self) -> String;
     fn to_uppercase(&self) -> Self;
     fn with_suffix(&self, suf: &str) -> Ident;
+    fn to_camel_case(&self) -> Self;
+    fn with_camel_suffix(&self, suf: &str) -> Ident;
 }
 
 impl IdentExt2 for Ident {
@@ -110,19 +135,35 @@ impl IdentExt2 for Ident {
     fn with_suffix(&self, suffix: &str) -> Ident {
         format_ident!("{}_{}", self.to_uppercase(), suffix)
     }
-}
 
-// line 131
-#[derive(Debug)]
-pub struct AsOption<T>(pub Option<T>);
+    #[inline]
+    fn to_camel_case(&self) -> Self {
+        // `hello_world` -> `HelloWorld`
+        let camel: String = self
+            .to_string_non_raw()
+            .split('_')
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            })
+            .collect();
+        format_ident!("{}", camel)
+    }
 
-impl<T> AsOption<T> {
     #[inline]
-    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> AsOption<U> {
-        AsOption(self.0.map(f))
+    fn with_camel_suffix(&self, suffix: &str) -> Ident {
+        format_ident!("{}{}", self.to_camel_case(), suffix)
     }
 }
 
+// line 131
+#[derive(Debug)]
+pub struct AsOption<T>(pub Option<T>);
+
 impl<T: ToTokens> ToTokens for AsOption<T> {
     fn to_tokens(&self, stream: &mut TokenStream2) {
         match &self.0 {
@@ -149,12 +190,147 @@ macro_rules! propagate_err {
     }};
 }
 
+// collect error to `Errors` and continue, `None` if error.
+macro_rules! collect_err {
+    ($errors:expr, $res:expr) => {{
+        match $res {
+            Ok(v) => Some(v),
+            Err(e) => {
+                $errors.push(e);
+                None
+            }
+        }
+    }};
+}
+
 // line 81
 #[inline]
 pub fn into_stream(e: Error) -> TokenStream {
     e.to_compile_error().into()
 }
 
+// report all errors at once, instead of stopping at the first one.
+#[derive(Debug, Default)]
+pub struct Errors(Option<Error>);
+
+impl Errors {
+    pub fn push(&mut self, e: Error) {
+        match &mut self.0 {
+            Some(errors) => errors.combine(e),
+            None => self.0 = Some(e),
+        }
+    }
+
+    pub fn finish(&mut self) -> SynResult<()> {
+        match self.0.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+// check the command function signature.
+// first two arguments are always `&Context` and `&ApplicationCommandInteraction`,
+// the rest are command options.
+pub fn create_declaration_validations(fun: &CommandFun) -> SynResult<()> {
+    if fun.args.len() < 2 {
+        return Err(Error::new(
+            fun.name.span(),
+            "the function must take `&Context` and `&ApplicationCommandInteraction` as first two arguments",
+        ));
+    }
+
+    for arg in &fun.args[..2] {
+        if !matches!(arg.kind, Type::Reference(_)) {
+            return Err(Error::new(
+                arg.kind.span(),
+                "this argument must be a reference",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// `&InteractionResponder` argument is not an option, it is passed by dispatcher.
+pub fn is_responder(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) => match &*reference.elem {
+            Type::Path(path) => path
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "InteractionResponder"),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+// command and option name must be 1-32 lowercase characters.
+pub fn validate_name(name: &str, span: Span) -> SynResult<()> {
+    let len = name.chars().count();
+    if len == 0 || len > MAX_NAME_LENGTH {
+        return Err(Error::new(
+            span,
+            format_args!(
+                "name must be 1-{} characters, but `{}` is {}",
+                MAX_NAME_LENGTH, name, len
+            ),
+        ));
+    }
+
+    let valid = name
+        .chars()
+        .all(|c| (c.is_alphanumeric() || c == '-' || c == '_') && !c.is_uppercase());
+    if !valid {
+        return Err(Error::new(
+            span,
+            format_args!(
+                "name must be lowercase letters, numbers, `-` or `_`, but got `{}`",
+                name
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+// context menu names are shown as is, so any characters are allowed.
+pub fn validate_context_menu_name(name: &str, span: Span) -> SynResult<()> {
+    let len = name.chars().count();
+    if len == 0 || len > MAX_NAME_LENGTH {
+        return Err(Error::new(
+            span,
+            format_args!(
+                "name must be 1-{} characters, but `{}` is {}",
+                MAX_NAME_LENGTH, name, len
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+// description must be 1-100 characters.
+pub fn validate_description(description: &str, span: Span) -> SynResult<()> {
+    let len = description.chars().count();
+    if len == 0 {
+        return Err(Error::new(span, "description must not be empty"));
+    }
+    if len > MAX_DESCRIPTION_LENGTH {
+        return Err(Error::new(
+            span,
+            format_args!(
+                "description must be at most {} characters, but is {}",
+                MAX_DESCRIPTION_LENGTH, len
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
 // line 275
 pub fn append_line(desc: &mut AsOption<String>, mut line: String) {
     if line.starts_with(' ') {
@@ -174,3 +350,67 @@ pub fn append_line(desc: &mut AsOption<String>, mut line: String) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lit(tokens: &str) -> Lit {
+        syn::parse_str(tokens).unwrap()
+    }
+
+    #[test]
+    fn lit_to_str() {
+        assert_eq!(lit(r#""ping""#).to_str().unwrap(), "ping");
+        assert_eq!(lit(r#"b"ping""#).to_str().unwrap(), "ping");
+        assert_eq!(lit("'p'").to_str().unwrap(), "p");
+        assert_eq!(lit("b'p'").to_str().unwrap(), "p");
+        assert!(lit("1").to_str().is_err());
+        assert!(lit(r#"b"\xff""#).to_str().is_err());
+    }
+
+    #[test]
+    fn lit_to_bool() {
+        assert!(lit("true").to_bool().unwrap());
+        assert!(!lit(r#""false""#).to_bool().unwrap());
+        assert!(lit(r#""yes""#).to_bool().is_err());
+        assert!(lit("1").to_bool().is_err());
+    }
+
+    #[test]
+    fn lit_to_ident() {
+        assert_eq!(lit(r#""is_admin""#).to_ident().unwrap(), "is_admin");
+        assert!(lit(r#""is admin""#).to_ident().is_err());
+        assert!(lit("1").to_ident().is_err());
+    }
+
+    #[test]
+    fn lit_to_u64() {
+        assert_eq!(lit("42").to_u64().unwrap(), 42);
+        assert_eq!(lit("42u64").to_u64().unwrap(), 42);
+        assert!(lit(r#""42""#).to_u64().is_err());
+        assert!(lit("1.5").to_u64().is_err());
+    }
+
+    #[test]
+    fn name_rules() {
+        let span = Span::call_site();
+        assert!(validate_name("ping", span).is_ok());
+        assert!(validate_name("set-nick_2", span).is_ok());
+        assert!(validate_name(&"a".repeat(MAX_NAME_LENGTH), span).is_ok());
+        assert!(validate_name(&"a".repeat(MAX_NAME_LENGTH + 1), span).is_err());
+        assert!(validate_name("", span).is_err());
+        assert!(validate_name("Ping", span).is_err());
+        assert!(validate_name("say hi", span).is_err());
+        assert!(validate_name("ping!", span).is_err());
+    }
+
+    #[test]
+    fn description_rules() {
+        let span = Span::call_site();
+        assert!(validate_description("Replies with pong", span).is_ok());
+        assert!(validate_description(&"é".repeat(MAX_DESCRIPTION_LENGTH), span).is_ok());
+        assert!(validate_description(&"a".repeat(MAX_DESCRIPTION_LENGTH + 1), span).is_err());
+        assert!(validate_description("", span).is_err());
+    }
+}
@@ -1,126 +1,238 @@
+mod autocomplete;
+mod checks;
+mod component;
+mod error;
 mod example;
-use serenity::builder::CreateApplicationCommand;
+#[cfg(test)]
+pub(crate) mod fixtures;
+mod id;
+mod numberinput;
+mod options;
+mod registry;
+mod report;
+mod responder;
+mod response;
+mod sync;
+mod welcome;
+use std::collections::HashSet;
+
+use serenity::async_trait;
+use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption};
+use serenity::http::Http;
 use serenity::model::{
     id::GuildId,
     interactions::{
         application_command::{
-            ApplicationCommandOptionType, ApplicationCommandInteraction
+            ApplicationCommandInteraction, ApplicationCommandInteractionDataOption,
+            ApplicationCommandType,
         },
-        Interaction, InteractionResponseType,
+        autocomplete::AutocompleteInteraction,
+        Interaction,
     },
 };
 use serenity::prelude::*;
 
-use tracing::error;
+use tracing::{error, info, warn};
+
+use crate::app_cmd_model::{Command, IntegerOutOfRange};
 
+pub use autocomplete::{
+    partial_input, AutocompleteChoice, AutocompleteFuture, AutocompleteValue,
+    MAX_AUTOCOMPLETE_CHOICES,
+};
+pub use checks::{check_permissions, run_checks, Check, CheckResult};
+pub use component::{
+    custom_id, parse_state, Component, ComponentId, ComponentResponse, ComponentResult,
+};
+pub use error::{CommandError, CommandResult};
+pub use options::{
+    check_choice, check_range, parse_option, target_message, target_user, CommandOptionsExt,
+    OptionError,
+};
+pub use registry::{ApplicationCommand, CommandMeta, CommandScope, DuplicateCommand, Registry};
+pub use responder::InteractionResponder;
+pub use response::CommandResponse;
+pub use sync::{
+    apply_sync, command_schema, desired_commands, fetch_commands, plan_sync, scoped_commands,
+    sync_commands, sync_targets, SyncAction, SyncReport, SyncTarget,
+};
+
+// every top level command, and context menu commands, submitted by the macros.
+// sub commands are reached from their parent, so they are left out.
+// submission order is not stable, so sorted by name.
+pub fn commands() -> Vec<&'static ApplicationCommand> {
+    let submitted = inventory::iter::<ApplicationCommand>
+        .into_iter()
+        .collect::<Vec<_>>();
+    let sub_commands = submitted
+        .iter()
+        .flat_map(|command| command.sub_commands)
+        .map(|sub_command| (sub_command.type_id)())
+        .collect::<HashSet<_>>();
+
+    let mut commands = submitted
+        .into_iter()
+        .filter(|command| !sub_commands.contains(&(command.type_id)()))
+        .collect::<Vec<_>>();
+    commands.sort_by_key(|command| (command.meta.name, command.meta.kind));
+    commands
+}
+
+// every component handler, submitted by `#[app_component]`.
+pub fn components() -> Vec<&'static Component> {
+    inventory::iter::<&'static Component>
+        .into_iter()
+        .copied()
+        .collect()
+}
+
+#[async_trait]
 pub trait ApplicationCommandTrait {
-    fn get_name()-> &'static str;
-    fn setup_app_cmd(cmd: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand;
-    fn interaction_handler(ctx: &Context, interaction: &ApplicationCommandInteraction) -> String;
+    // nesting level of sub commands. 0 mean no sub commands.
+    const DEPTH: usize = 0;
+    const META: CommandMeta;
+    // registered with their parent, and dispatched by path.
+    // the parent is never called, only its sub commands are.
+    const SUB_COMMANDS: &'static [ApplicationCommand] = &[];
+    // `#[only_in]` is set. only top level commands have a scope.
+    const SCOPED: bool = false;
+
+    // definition sent to discord, sub commands are included as options.
+    fn model() -> Command;
+
+    fn setup_app_cmd(
+        cmd: &mut CreateApplicationCommand,
+    ) -> Result<&mut CreateApplicationCommand, IntegerOutOfRange> {
+        Self::model().build(cmd)
+    }
+    // register as sub command (or sub command group) of other command.
+    fn setup_sub_command(
+        option: &mut CreateApplicationCommandOption,
+    ) -> Result<&mut CreateApplicationCommandOption, IntegerOutOfRange> {
+        Self::model().into_sub_command().build(option)
+    }
+
+    // `options` is top level options, or nested options of sub command.
+    // middleware already ran, so only parse options and run the command.
+    async fn options_handler(
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+        responder: &InteractionResponder<'_>,
+        options: &[ApplicationCommandInteractionDataOption],
+    ) -> CommandResult;
+
+    // suggestions for the focused option.
+    // generated if any option has `#[autocomplete = ...]`.
+    async fn autocomplete(
+        _ctx: &Context,
+        _interaction: &AutocompleteInteraction,
+        _options: &[ApplicationCommandInteractionDataOption],
+    ) -> Vec<AutocompleteChoice> {
+        Vec::new()
+    }
 }
 
-pub async fn setup_app_cmd(ctx: &Context) {
+// guild for `#[only_in(dev)]` commands, from `GUILD_ID`.
+pub fn dev_guild() -> Option<GuildId> {
     use std::env;
-    let guild_id = GuildId(
-        env::var("GUILD_ID")
-            .expect("Expected GUILD_ID in environment")
-            .parse()
-            .expect("GUILD_ID must be an integer"),
-    );
-
-    let _commands = GuildId::set_application_commands(&guild_id, &ctx.http, |commands| {
-        commands
-            .create_application_command(example::ExampleCommand::setup_app_cmd)
-            .create_application_command(|command| {
-                command
-                    .name("id")
-                    .description("Get a user id")
-                    .create_option(|option| {
-                        option
-                            .name("id")
-                            .description("The user to lookup")
-                            .kind(ApplicationCommandOptionType::User)
-                            .required(true)
-                    })
-            })
-            .create_application_command(|command| {
-                command
-                    .name("welcome")
-                    .description("Welcome a user")
-                    .create_option(|option| {
-                        option
-                            .name("user")
-                            .description("The user to welcome")
-                            .kind(ApplicationCommandOptionType::User)
-                            .required(true)
-                    })
-                    .create_option(|option| {
-                        option
-                            .name("message")
-                            .description("The message to send")
-                            .kind(ApplicationCommandOptionType::String)
-                            .required(true)
-                            .add_string_choice(
-                                "Welcome to our cool server! Ask me if you need help",
-                                "pizza",
-                            )
-                            .add_string_choice("Hey, do you want a coffee?", "coffee")
-                            .add_string_choice(
-                                "Welcome to the club, you're now a good person. Well, I hope.",
-                                "club",
-                            )
-                            .add_string_choice(
-                                "I hope that you brought a controller to play together!",
-                                "game",
-                            )
-                    })
-            })
-            .create_application_command(|command| {
-                command
-                    .name("numberinput")
-                    .description("Test command for number input")
-                    .create_option(|option| {
-                        option
-                            .name("int")
-                            .description("An integer from 5 to 10")
-                            .kind(ApplicationCommandOptionType::Integer)
-                            .min_int_value(5)
-                            .max_int_value(10)
-                            .required(true)
-                    })
-                    .create_option(|option| {
-                        option
-                            .name("number")
-                            .description("A float from -3.3 to 234.5")
-                            .kind(ApplicationCommandOptionType::Number)
-                            .min_number_value(-3.3)
-                            .max_number_value(234.5)
-                            .required(true)
-                    })
-            })
-    })
-    .await;
+    match env::var("GUILD_ID").map(|id| id.parse()) {
+        Ok(Ok(id)) => Some(GuildId(id)),
+        Ok(Err(why)) => {
+            warn!("GUILD_ID must be an integer: {}", why);
+            None
+        }
+        Err(_) => None,
+    }
 }
 
-pub async fn interaction_handler(ctx: Context, interaction: Interaction) {
-    if let Interaction::ApplicationCommand(command) = interaction {
-        let content = match command.data.name.as_str() {
-            "ping" => example::ExampleCommand::interaction_handler(&ctx, &command),
-            name => {
-                error!("You forgot this cmd {}", name);
-                "This command is not found, so please report to bot dev.".to_string()
+// register commands to discord, by their scope. only changed commands are sent.
+// a target that fails is counted in the report, and the others are still synced.
+pub async fn setup_app_cmd(registry: &Registry, http: &Http) -> SyncReport {
+    let dev_guild = dev_guild();
+    if dev_guild.is_none()
+        && registry
+            .commands()
+            .any(|command| command.meta.scope == CommandScope::Dev)
+    {
+        warn!("GUILD_ID is not set, so dev guild commands are not registered");
+    }
+
+    let mut report = SyncReport::default();
+    for target in sync_targets(registry, dev_guild) {
+        match sync_commands(http, registry, target, dev_guild).await {
+            Ok(target_report) => {
+                info!("synced {}: {}", target, target_report);
+                report.merge(target_report);
+            }
+            Err(why) => {
+                error!("cannot sync {}: {}", target, why);
+                report.failed_targets += 1;
+            }
+        }
+    }
+
+    let mut paths = registry.iter().collect::<Vec<_>>();
+    paths.sort_by_key(|(path, _)| *path);
+    for (path, command) in paths {
+        match command.meta.kind {
+            ApplicationCommandType::User => info!("user cmd {}", path),
+            ApplicationCommandType::Message => info!("message cmd {}", path),
+            _ => info!("slash cmd /{}: {}", path, command.meta.description),
+        }
+    }
+
+    report
+}
+
+pub async fn interaction_handler(registry: &Registry, ctx: Context, interaction: Interaction) {
+    match interaction {
+        Interaction::ApplicationCommand(command) => {
+            let responder = InteractionResponder::new(&ctx.http, &command);
+            let result = registry.dispatch(&ctx, &command, &responder).await;
+
+            let response = match result {
+                Ok(response) => response,
+                Err(why) => {
+                    error!("slash cmd {} failed: {}", command.data.name, why);
+                    // errors are only for the invoking user.
+                    CommandResponse::from(why.user_message()).ephemeral(true)
+                }
+            };
+
+            if let Err(why) = responder.respond(response).await {
+                error!("cannot res to slash cmd: {}", why);
+            }
+        }
+        Interaction::Autocomplete(autocomplete) => {
+            let choices = match registry.dispatch_autocomplete(&ctx, &autocomplete).await {
+                Ok(choices) => choices,
+                Err(why) => {
+                    error!("autocomplete {} failed: {}", autocomplete.data.name, why);
+                    Vec::new()
+                }
+            };
+
+            if let Err(why) = autocomplete::send_choices(&ctx.http, &autocomplete, choices).await {
+                error!("cannot res to autocomplete: {}", why);
+            }
+        }
+        Interaction::MessageComponent(component) => {
+            let result = registry.dispatch_component(&ctx, &component).await;
+
+            let response = match result {
+                Ok(response) => response,
+                Err(why) => {
+                    error!("component {} failed: {}", component.data.custom_id, why);
+                    ComponentResponse::Reply(
+                        CommandResponse::from(why.user_message()).ephemeral(true),
+                    )
+                }
+            };
+
+            if let Err(why) = response.send(&ctx.http, &component).await {
+                error!("cannot res to component: {}", why);
             }
-        };
-
-        if let Err(why) = command
-            .create_interaction_response(&ctx.http, |response| {
-                response
-                    .kind(InteractionResponseType::ChannelMessageWithSource)
-                    .interaction_response_data(|message| message.content(content))
-            })
-            .await
-        {
-            error!("cannot res to slash cmd: {}", why);
         }
+        _ => {}
     }
 }
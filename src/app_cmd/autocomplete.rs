@@ -0,0 +1,105 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use serenity::http::Http;
+use serenity::model::interactions::{
+    application_command::ApplicationCommandInteractionDataOption,
+    autocomplete::AutocompleteInteraction,
+};
+use serenity::Result as SerenityResult;
+
+// discord shows at most 25 suggestions.
+pub const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AutocompleteValue {
+    String(String),
+    Integer(i64),
+    Number(f64),
+}
+
+impl From<String> for AutocompleteValue {
+    fn from(value: String) -> Self {
+        AutocompleteValue::String(value)
+    }
+}
+
+impl From<&str> for AutocompleteValue {
+    fn from(value: &str) -> Self {
+        AutocompleteValue::String(value.to_string())
+    }
+}
+
+impl From<i64> for AutocompleteValue {
+    fn from(value: i64) -> Self {
+        AutocompleteValue::Integer(value)
+    }
+}
+
+impl From<f64> for AutocompleteValue {
+    fn from(value: f64) -> Self {
+        AutocompleteValue::Number(value)
+    }
+}
+
+// a suggestion, `name` is shown and `value` is sent as the option value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutocompleteChoice {
+    pub name: String,
+    pub value: AutocompleteValue,
+}
+
+impl AutocompleteChoice {
+    pub fn new(name: impl ToString, value: impl Into<AutocompleteValue>) -> Self {
+        AutocompleteChoice {
+            name: name.to_string(),
+            value: value.into(),
+        }
+    }
+}
+
+// string suggestion, shown as is.
+impl From<String> for AutocompleteChoice {
+    fn from(value: String) -> Self {
+        AutocompleteChoice::new(&value, value.clone())
+    }
+}
+
+impl From<&str> for AutocompleteChoice {
+    fn from(value: &str) -> Self {
+        AutocompleteChoice::new(value, value)
+    }
+}
+
+pub type AutocompleteFuture<'a> =
+    Pin<Box<dyn Future<Output = Vec<AutocompleteChoice>> + Send + 'a>>;
+
+// what the user typed so far. numbers are also sent as typed.
+pub fn partial_input(option: &ApplicationCommandInteractionDataOption) -> String {
+    match &option.value {
+        Some(value) => match value.as_str() {
+            Some(s) => s.to_string(),
+            None => value.to_string(),
+        },
+        None => String::new(),
+    }
+}
+
+pub async fn send_choices(
+    http: impl AsRef<Http>,
+    interaction: &AutocompleteInteraction,
+    choices: Vec<AutocompleteChoice>,
+) -> SerenityResult<()> {
+    interaction
+        .create_autocomplete_response(http, |response| {
+            for choice in choices.into_iter().take(MAX_AUTOCOMPLETE_CHOICES) {
+                match choice.value {
+                    AutocompleteValue::String(s) => response.add_string_choice(choice.name, s),
+                    AutocompleteValue::Integer(i) => response.add_int_choice(choice.name, i),
+                    AutocompleteValue::Number(n) => response.add_number_choice(choice.name, n),
+                };
+            }
+            response
+        })
+        .await
+}
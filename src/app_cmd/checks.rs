@@ -0,0 +1,209 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+
+use serenity::model::{
+    interactions::application_command::ApplicationCommandInteraction, permissions::Permissions,
+};
+use serenity::prelude::*;
+
+// invoking member lacks these permissions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingPermissions(pub Permissions);
+
+impl fmt::Display for MissingPermissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "You need {:?} permissions to use this command.", self.0)
+    }
+}
+
+impl std::error::Error for MissingPermissions {}
+
+// invoking member must have all of `required` permissions.
+// not in guild (no member) mean no permissions.
+pub fn check_permissions(
+    interaction: &ApplicationCommandInteraction,
+    required: Permissions,
+) -> Result<(), MissingPermissions> {
+    if required.is_empty() {
+        return Ok(());
+    }
+
+    let permissions = interaction
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+        .unwrap_or_else(Permissions::empty);
+
+    if permissions.administrator() || permissions.contains(required) {
+        Ok(())
+    } else {
+        Err(MissingPermissions(required - permissions))
+    }
+}
+
+pub type CheckResult = Result<(), String>;
+pub type CheckFuture<'a> = Pin<Box<dyn Future<Output = CheckResult> + Send + 'a>>;
+
+// generated by `#[app_check]` as `<NAME>_CHECK` static.
+pub struct Check {
+    pub name: &'static str,
+    pub function: for<'a> fn(&'a Context, &'a ApplicationCommandInteraction) -> CheckFuture<'a>,
+}
+
+// run checks in order, stop at the first failure and return its reason.
+pub async fn run_checks(
+    ctx: &Context,
+    interaction: &ApplicationCommandInteraction,
+    checks: &[&Check],
+) -> CheckResult {
+    for check in checks {
+        if let Err(reason) = (check.function)(ctx, interaction).await {
+            if reason.is_empty() {
+                return Err(format!("Check `{}` failed.", check.name));
+            }
+            return Err(reason);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use serde_json::json;
+    use serenity::cache::Cache;
+    use serenity::client::bridge::gateway::ShardMessenger;
+    use serenity::futures::channel::mpsc;
+    use serenity::http::Http;
+
+    use macro_util::app_check;
+
+    use super::*;
+    use crate::app_cmd::fixtures::test_interaction;
+
+    // checks only get `Context` passed through, so it does not need a connection.
+    fn context() -> Context {
+        Context {
+            data: Arc::new(RwLock::new(TypeMap::new())),
+            shard: ShardMessenger::new(mpsc::unbounded().0),
+            shard_id: 0,
+            http: Arc::new(Http::new_with_token("")),
+            cache: Arc::new(Cache::default()),
+        }
+    }
+
+    #[test]
+    fn permissions_of_member() {
+        let required = Permissions::KICK_MEMBERS | Permissions::BAN_MEMBERS;
+
+        let both = test_interaction(json!({}), Some(&required.bits().to_string()));
+        assert_eq!(check_permissions(&both, required), Ok(()));
+
+        let kick = test_interaction(
+            json!({}),
+            Some(&Permissions::KICK_MEMBERS.bits().to_string()),
+        );
+        assert_eq!(
+            check_permissions(&kick, required),
+            Err(MissingPermissions(Permissions::BAN_MEMBERS))
+        );
+    }
+
+    #[test]
+    fn administrator_has_every_permission() {
+        let admin = Permissions::ADMINISTRATOR.bits().to_string();
+        let interaction = test_interaction(json!({}), Some(&admin));
+
+        assert_eq!(
+            check_permissions(&interaction, Permissions::MANAGE_GUILD),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn no_permissions_without_member() {
+        // in dm, there is only `user`.
+        let interaction = test_interaction(json!({}), None);
+        assert!(interaction.member.is_none());
+
+        assert_eq!(
+            check_permissions(&interaction, Permissions::SEND_MESSAGES),
+            Err(MissingPermissions(Permissions::SEND_MESSAGES))
+        );
+        assert_eq!(
+            check_permissions(&interaction, Permissions::empty()),
+            Ok(())
+        );
+    }
+
+    // checks run by `ordered_checks`, in call order.
+    static CALLS: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+
+    #[app_check]
+    async fn first(_ctx: &Context, _interaction: &ApplicationCommandInteraction) -> CheckResult {
+        CALLS.lock().unwrap().push("first");
+        Ok(())
+    }
+
+    #[app_check]
+    async fn second(_ctx: &Context, _interaction: &ApplicationCommandInteraction) -> CheckResult {
+        CALLS.lock().unwrap().push("second");
+        Err("Second check failed.".to_string())
+    }
+
+    #[app_check]
+    async fn third(_ctx: &Context, _interaction: &ApplicationCommandInteraction) -> CheckResult {
+        CALLS.lock().unwrap().push("third");
+        Err("Third check failed.".to_string())
+    }
+
+    #[app_check]
+    async fn allowed(_ctx: &Context, _interaction: &ApplicationCommandInteraction) -> CheckResult {
+        Ok(())
+    }
+
+    #[app_check]
+    async fn silent(_ctx: &Context, _interaction: &ApplicationCommandInteraction) -> CheckResult {
+        Err(String::new())
+    }
+
+    #[tokio::test]
+    async fn ordered_checks() {
+        let result = run_checks(
+            &context(),
+            &test_interaction(json!({}), None),
+            &[&FIRST_CHECK, &SECOND_CHECK, &THIRD_CHECK],
+        )
+        .await;
+
+        // the first failure is returned, and later checks are not run.
+        assert_eq!(result, Err("Second check failed.".to_string()));
+        assert_eq!(*CALLS.lock().unwrap(), ["first", "second"]);
+    }
+
+    #[tokio::test]
+    async fn passing_checks() {
+        let (ctx, interaction) = (context(), test_interaction(json!({}), None));
+
+        assert_eq!(run_checks(&ctx, &interaction, &[]).await, Ok(()));
+        assert_eq!(
+            run_checks(&ctx, &interaction, &[&ALLOWED_CHECK, &ALLOWED_CHECK]).await,
+            Ok(())
+        );
+    }
+
+    #[tokio::test]
+    async fn empty_reason() {
+        let result = run_checks(
+            &context(),
+            &test_interaction(json!({}), None),
+            &[&ALLOWED_CHECK, &SILENT_CHECK],
+        )
+        .await;
+
+        assert_eq!(result, Err("Check `silent` failed.".to_string()));
+    }
+}
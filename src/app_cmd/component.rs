@@ -0,0 +1,212 @@
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+
+use serenity::http::Http;
+use serenity::model::{
+    id::UserId,
+    interactions::{message_component::MessageComponentInteraction, InteractionResponseType},
+};
+use serenity::prelude::*;
+use serenity::Result as SerenityResult;
+
+use super::{CommandError, CommandResponse};
+
+// discord limit of `custom_id` length.
+pub const MAX_CUSTOM_ID_LENGTH: usize = 100;
+
+// what a component handler replies with.
+#[derive(Debug, Clone)]
+pub enum ComponentResponse {
+    // edit the message the component is attached to.
+    Update(CommandResponse),
+    // reply with a new message.
+    Reply(CommandResponse),
+    // acknowledge only, the message is not changed.
+    Acknowledge,
+}
+
+pub type ComponentResult = Result<ComponentResponse, CommandError>;
+
+pub type ComponentFuture<'a> = Pin<Box<dyn Future<Output = ComponentResult> + Send + 'a>>;
+
+// generated by `#[app_component]` as `<NAME>_COMPONENT` static.
+pub struct Component {
+    // first part of `custom_id`, used to route to this handler.
+    pub prefix: &'static str,
+    pub function:
+        for<'a> fn(&'a Context, &'a MessageComponentInteraction, &'a str) -> ComponentFuture<'a>,
+}
+
+// every component submits its static, see `crate::app_cmd::components`.
+inventory::collect!(&'static Component);
+
+// parsed `custom_id`, `<prefix>:<owner>:<state>`. owner is empty if anyone can use it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentId<'a> {
+    pub prefix: &'a str,
+    pub owner: Option<UserId>,
+    pub state: &'a str,
+}
+
+impl<'a> ComponentId<'a> {
+    pub fn parse(custom_id: &'a str) -> Option<Self> {
+        let mut parts = custom_id.splitn(3, ':');
+        let prefix = parts.next()?;
+        let owner = match parts.next()? {
+            "" => None,
+            owner => Some(UserId(owner.parse().ok()?)),
+        };
+        let state = parts.next()?;
+
+        Some(ComponentId {
+            prefix,
+            owner,
+            state,
+        })
+    }
+}
+
+impl fmt::Display for ComponentId<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.owner {
+            Some(owner) => write!(f, "{}:{}:{}", self.prefix, owner, self.state),
+            None => write!(f, "{}::{}", self.prefix, self.state),
+        }
+    }
+}
+
+// build `custom_id` for a component routed to `component`.
+// `owner` is the only user who can use it, if set.
+pub fn custom_id(component: &Component, owner: Option<UserId>, state: impl fmt::Display) -> String {
+    let state = state.to_string();
+    let id = ComponentId {
+        prefix: component.prefix,
+        owner,
+        state: &state,
+    }
+    .to_string();
+
+    debug_assert!(
+        id.len() <= MAX_CUSTOM_ID_LENGTH,
+        "custom_id is longer than {} characters: {}",
+        MAX_CUSTOM_ID_LENGTH,
+        id
+    );
+    id
+}
+
+// used by `#[app_component]` to convert state to the handler argument type.
+pub fn parse_state<T: FromStr>(state: &str) -> Result<T, CommandError> {
+    state.parse().map_err(|_| {
+        CommandError::Other("This component is outdated, so please try again.".to_string())
+    })
+}
+
+impl ComponentResponse {
+    pub fn update(response: impl Into<CommandResponse>) -> Self {
+        ComponentResponse::Update(response.into())
+    }
+
+    pub fn reply(response: impl Into<CommandResponse>) -> Self {
+        ComponentResponse::Reply(response.into())
+    }
+
+    pub async fn send(
+        self,
+        http: impl AsRef<Http>,
+        component: &MessageComponentInteraction,
+    ) -> SerenityResult<()> {
+        let http = http.as_ref();
+
+        let (kind, response) = match self {
+            ComponentResponse::Update(response) => {
+                (InteractionResponseType::UpdateMessage, response)
+            }
+            ComponentResponse::Reply(response) => {
+                (InteractionResponseType::ChannelMessageWithSource, response)
+            }
+            ComponentResponse::Acknowledge => {
+                return component
+                    .create_interaction_response(http, |r| {
+                        r.kind(InteractionResponseType::DeferredUpdateMessage)
+                    })
+                    .await;
+            }
+        };
+
+        component
+            .create_interaction_response(http, |r| {
+                r.kind(kind)
+                    .interaction_response_data(|data| response.fill_data(data))
+            })
+            .await?;
+
+        // initial response cannot have files.
+        if !response.attachments.is_empty() {
+            let files = CommandResponse {
+                attachments: response.attachments,
+                ephemeral: response.ephemeral,
+                ..Default::default()
+            };
+            component
+                .create_followup_message(http, |followup| files.fill_followup(followup))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<CommandResponse> for ComponentResponse {
+    // new message by default, same as commands.
+    fn from(response: CommandResponse) -> Self {
+        ComponentResponse::Reply(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macro_util::app_component;
+
+    use super::*;
+    use crate::app_cmd::{ComponentResult, DuplicateCommand, Registry};
+
+    #[app_component("vote")]
+    async fn vote_button(
+        _ctx: &Context,
+        _component: &MessageComponentInteraction,
+        choice: &str,
+    ) -> ComponentResult {
+        Ok(ComponentResponse::update(format!("voted {}", choice)))
+    }
+
+    #[test]
+    fn component_ids() {
+        assert_eq!(VOTE_BUTTON_COMPONENT.prefix, "vote");
+
+        let id = custom_id(&VOTE_BUTTON_COMPONENT, Some(UserId(42)), "yes:1");
+        assert_eq!(id, "vote:42:yes:1");
+        assert_eq!(
+            ComponentId::parse(&id),
+            Some(ComponentId {
+                prefix: "vote",
+                owner: Some(UserId(42)),
+                state: "yes:1",
+            })
+        );
+
+        let id = custom_id(&VOTE_BUTTON_COMPONENT, None, 3);
+        assert_eq!(ComponentId::parse(&id).unwrap().owner, None);
+        assert_eq!(ComponentId::parse("vote"), None);
+
+        let registry = Registry::new([]).unwrap();
+        assert_eq!(
+            registry
+                .with_components([&VOTE_BUTTON_COMPONENT, &VOTE_BUTTON_COMPONENT])
+                .err(),
+            Some(DuplicateCommand("vote:".to_string()))
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use std::fmt;
+use std::time::Duration;
+
+use serenity::prelude::SerenityError;
+
+use super::checks::MissingPermissions;
+use super::options::OptionError;
+use super::response::CommandResponse;
+
+pub type CommandResult = Result<CommandResponse, CommandError>;
+
+#[derive(Debug)]
+pub enum CommandError {
+    // invalid or missing option.
+    Option(OptionError),
+    // invoking member lacks required permissions.
+    Permission(MissingPermissions),
+    // a check failed, with its reason.
+    Check(String),
+    // command is used again too soon, with the remaining time.
+    Cooldown(Duration),
+    // discord api error, not shown to users.
+    Serenity(SerenityError),
+    // any other failure, shown to users as is.
+    Other(String),
+}
+
+impl CommandError {
+    // message for the invoking user.
+    pub fn user_message(&self) -> String {
+        match self {
+            CommandError::Serenity(_) => {
+                "Something went wrong, so please report to bot dev.".to_string()
+            }
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Option(why) => why.fmt(f),
+            CommandError::Permission(why) => why.fmt(f),
+            CommandError::Check(reason) => f.write_str(reason),
+            CommandError::Cooldown(remaining) => write!(
+                f,
+                "Please wait {:.1} seconds before using this command again.",
+                remaining.as_secs_f64()
+            ),
+            CommandError::Serenity(why) => why.fmt(f),
+            CommandError::Other(why) => f.write_str(why),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<OptionError> for CommandError {
+    fn from(why: OptionError) -> Self {
+        CommandError::Option(why)
+    }
+}
+
+impl From<MissingPermissions> for CommandError {
+    fn from(why: MissingPermissions) -> Self {
+        CommandError::Permission(why)
+    }
+}
+
+impl From<SerenityError> for CommandError {
+    fn from(why: SerenityError) -> Self {
+        CommandError::Serenity(why)
+    }
+}
@@ -1,20 +1,93 @@
-use super::ApplicationCommandTrait;
-use serenity::builder::CreateApplicationCommand;
-use serenity::model::interactions::application_command::ApplicationCommandInteraction;
+use macro_util::{app_component, application_command};
+use serenity::model::{
+    id::UserId,
+    interactions::{
+        application_command::ApplicationCommandInteraction,
+        autocomplete::AutocompleteInteraction,
+        message_component::{ButtonStyle, MessageComponentInteraction},
+    },
+};
 use serenity::prelude::*;
 
-pub struct ExampleCommand;
+use super::{custom_id, CommandResponse, CommandResult, ComponentResponse, ComponentResult};
 
-//TODO: remake to use proc macro
-impl ApplicationCommandTrait for ExampleCommand {
-    fn get_name() -> &'static str {
-        "ping"
-    }
-    fn setup_app_cmd(cmd: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
-        cmd.name("ping").description("A ping command")
-    }
+// reply with a button, only the invoking user can press it.
+fn ping_response(user: UserId, count: u32) -> CommandResponse {
+    CommandResponse::new()
+        .content(format!("Hi x{}", count))
+        .components(|components| {
+            components.create_action_row(|row| {
+                row.create_button(|button| {
+                    button
+                        .style(ButtonStyle::Primary)
+                        .label("Again")
+                        .custom_id(custom_id(&AGAIN_COMPONENT, Some(user), count + 1))
+                })
+            })
+        })
+}
+
+/// A ping command
+#[application_command("ping")]
+pub async fn example(_ctx: &Context, interaction: &ApplicationCommandInteraction) -> CommandResult {
+    Ok(ping_response(interaction.user.id, 1))
+}
+
+#[app_component]
+pub async fn again(
+    _ctx: &Context,
+    component: &MessageComponentInteraction,
+    count: u32,
+) -> ComponentResult {
+    Ok(ComponentResponse::update(ping_response(
+        component.user.id,
+        count,
+    )))
+}
+
+// a parent needs a body to be a command function, but it is never called.
+// discord invokes only its sub commands, and `#[only_in]` is set here, not on them.
+/// Sub command example
+#[application_command("example", sub_commands(echo, nested))]
+pub async fn example_tree(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+) -> CommandResult {
+    Ok("Please use a sub command".into())
+}
+
+/// Echo a message
+#[application_command]
+async fn echo(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+    #[description = "The message to echo"]
+    #[autocomplete = echo_suggestions]
+    message: String,
+) -> CommandResult {
+    Ok(message.into())
+}
+
+async fn echo_suggestions(
+    _ctx: &Context,
+    _interaction: &AutocompleteInteraction,
+    partial: &str,
+) -> Vec<&'static str> {
+    ["hello", "hello world", "good morning", "good night"]
+        .into_iter()
+        .filter(|message| message.starts_with(partial))
+        .collect()
+}
+
+// never called too, like `example_tree`.
+/// Sub command group example
+#[application_command(sub_commands(pong))]
+async fn nested(_ctx: &Context, _interaction: &ApplicationCommandInteraction) -> CommandResult {
+    Ok("Please use a sub command".into())
+}
 
-    fn interaction_handler(_ctx: &Context, _interaction: &ApplicationCommandInteraction) -> String {
-        "Hi".to_string()
-    }
+/// Reply pong
+#[application_command]
+async fn pong(_ctx: &Context, _interaction: &ApplicationCommandInteraction) -> CommandResult {
+    Ok("pong".into())
 }
@@ -0,0 +1,196 @@
+// commands and interactions shared by the tests of app_cmd.
+use serde_json::{json, Value};
+use serenity::model::{
+    interactions::{
+        application_command::ApplicationCommandInteraction, autocomplete::AutocompleteInteraction,
+    },
+    user::User,
+};
+use serenity::prelude::*;
+
+use macro_util::{app_check, application_command, user_command};
+
+use super::{
+    AutocompleteChoice, CheckResult, CommandResponse, CommandResult, InteractionResponder,
+};
+
+/// Say hello
+/// to the world.
+#[application_command]
+pub(crate) async fn hello_world(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+) -> CommandResult {
+    Ok("Hello World!".into())
+}
+
+/// Repeat a message.
+#[application_command]
+pub(crate) async fn repeat(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+    #[description = "The message to repeat"] message: String,
+    #[description = "How many times"] times: Option<i64>,
+) -> CommandResult {
+    Ok(message.repeat(times.unwrap_or(1) as usize).into())
+}
+
+/// Pick a drink.
+#[application_command]
+pub(crate) async fn drink(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+    #[description = "The drink"]
+    #[choice("Coffee", "coffee")]
+    #[choice("Tea", "tea")]
+    drink: String,
+) -> CommandResult {
+    Ok(drink.into())
+}
+
+/// Search a word.
+#[application_command]
+pub(crate) async fn search(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+    #[description = "The word"]
+    #[autocomplete = suggest_words]
+    word: String,
+) -> CommandResult {
+    Ok(word.into())
+}
+
+async fn suggest_words(
+    _ctx: &Context,
+    _interaction: &AutocompleteInteraction,
+    partial: &str,
+) -> Vec<AutocompleteChoice> {
+    vec![AutocompleteChoice::new(partial, partial)]
+}
+
+/// Roll a dice.
+#[application_command]
+pub(crate) async fn roll(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+    #[description = "Faces"]
+    #[min = 1]
+    #[max = 100]
+    faces: i64,
+    #[description = "Bias"]
+    #[min = -0.5]
+    bias: Option<f64>,
+) -> CommandResult {
+    Ok(format!("{} {:?}", faces, bias).into())
+}
+
+/// Manage settings.
+#[application_command(sub_commands(greeting))]
+#[required_permissions(BAN_MEMBERS, "KICK_MEMBERS | MANAGE_GUILD")]
+pub(crate) async fn settings(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+) -> CommandResult {
+    Ok(String::new().into())
+}
+
+/// Greeting settings.
+#[application_command(sub_commands(set))]
+pub(crate) async fn greeting(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+) -> CommandResult {
+    Ok(String::new().into())
+}
+
+#[app_check]
+pub(crate) async fn in_guild(
+    _ctx: &Context,
+    interaction: &ApplicationCommandInteraction,
+) -> CheckResult {
+    match interaction.guild_id {
+        Some(_) => Ok(()),
+        None => Err("This command is only available in a server.".to_string()),
+    }
+}
+
+/// Set greeting.
+#[application_command]
+#[checks(in_guild)]
+#[cooldown = 5]
+#[ephemeral]
+pub(crate) async fn set(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+    #[description = "The greeting"] text: String,
+) -> CommandResult {
+    Ok(text.into())
+}
+
+/// Take a while.
+#[application_command]
+#[defer]
+pub(crate) async fn slow(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+    responder: &InteractionResponder<'_>,
+    #[description = "The message to send later"] message: String,
+) -> CommandResult {
+    responder.followup(message.into()).await?;
+    Ok(CommandResponse::new())
+}
+
+#[user_command("User info")]
+pub(crate) async fn user_info(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+    user: &User,
+) -> CommandResult {
+    Ok(user.tag().into())
+}
+
+// a slash command interaction as discord sends it.
+// `data` is merged into the command data, like `options` and `resolved`.
+// with `member_permissions`, it is used by a guild member, otherwise in dms.
+pub(crate) fn test_interaction(
+    data: Value,
+    member_permissions: Option<&str>,
+) -> ApplicationCommandInteraction {
+    let user = json!({
+        "id": "300",
+        "username": "tester",
+        "discriminator": "0001",
+        "avatar": null,
+    });
+    let mut payload = json!({
+        "id": "100",
+        "application_id": "200",
+        "type": 2,
+        "data": { "id": "400", "name": "ping", "type": 1 },
+        "channel_id": "500",
+        "token": "token",
+        "version": 1,
+        "locale": "en-US",
+    });
+    if let Value::Object(data) = data {
+        for (key, value) in data {
+            payload["data"][key] = value;
+        }
+    }
+    match member_permissions {
+        Some(permissions) => {
+            payload["guild_id"] = Value::from("600");
+            payload["member"] = json!({
+                "user": user,
+                "roles": [],
+                "joined_at": "2021-01-01T00:00:00+00:00",
+                "deaf": false,
+                "mute": false,
+                "permissions": permissions,
+            });
+        }
+        None => payload["user"] = user,
+    }
+
+    serde_json::from_value(payload).unwrap()
+}
@@ -0,0 +1,42 @@
+use macro_util::{application_command, user_command};
+use serenity::model::{
+    interactions::application_command::ApplicationCommandInteraction, user::User,
+};
+use serenity::prelude::*;
+
+use super::{CommandResponse, CommandResult};
+
+fn id_response(user: &User) -> CommandResponse {
+    CommandResponse::new().embed(|embed| {
+        embed
+            .title(user.tag())
+            .thumbnail(user.face())
+            .field("id", user.id, false)
+            .field(
+                "created at",
+                format!("<t:{}:F>", user.created_at().timestamp()),
+                false,
+            )
+    })
+}
+
+/// Get a user id
+#[application_command]
+pub async fn id(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+    #[description = "The user to lookup"] id: User,
+) -> CommandResult {
+    Ok(id_response(&id))
+}
+
+/// Get a user id from the user menu
+#[user_command("Get ID")]
+#[ephemeral]
+pub async fn get_id(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+    user: &User,
+) -> CommandResult {
+    Ok(id_response(user))
+}
@@ -0,0 +1,22 @@
+use macro_util::application_command;
+use serenity::model::interactions::application_command::ApplicationCommandInteraction;
+use serenity::prelude::*;
+
+use super::CommandResult;
+
+/// Test command for number input
+#[application_command]
+pub async fn numberinput(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+    #[description = "An integer from 5 to 10"]
+    #[min = 5]
+    #[max = 10]
+    int: i64,
+    #[description = "A float from -3.3 to 234.5"]
+    #[min = -3.3]
+    #[max = 234.5]
+    number: f64,
+) -> CommandResult {
+    Ok(format!("int: {}, number: {}", int, number).into())
+}
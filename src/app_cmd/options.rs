@@ -0,0 +1,414 @@
+use std::fmt;
+
+use serenity::model::{
+    channel::{Message, PartialChannel},
+    guild::Role,
+    id::{ChannelId, RoleId, UserId},
+    interactions::application_command::{
+        ApplicationCommandInteraction, ApplicationCommandInteractionDataOption,
+        ApplicationCommandInteractionDataOptionValue as OptionValue, ApplicationCommandOptionType,
+        ResolvedTarget,
+    },
+    user::User,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptionError {
+    // required option is not sent.
+    Missing(String),
+    // sent value is not the registered type.
+    InvalidType(String),
+    // sent value is not one of the registered choices.
+    InvalidChoice(String),
+    // sent value is not in the registered range.
+    OutOfRange(String),
+}
+
+impl fmt::Display for OptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptionError::Missing(name) => write!(f, "option `{}` is required", name),
+            OptionError::InvalidType(name) => write!(f, "option `{}` has an invalid value", name),
+            OptionError::InvalidChoice(name) => {
+                write!(f, "option `{}` must be one of the choices", name)
+            }
+            OptionError::OutOfRange(name) => write!(f, "option `{}` is out of range", name),
+        }
+    }
+}
+
+impl std::error::Error for OptionError {}
+
+// convert resolved option value to rust type.
+// `None` mean the option is not sent.
+pub trait FromCommandOption: Sized {
+    fn from_option(value: Option<&OptionValue>) -> Option<Self>;
+
+    // only `Option<T>` accept missing value.
+    fn missing() -> Option<Self> {
+        None
+    }
+}
+
+macro_rules! impl_from_option {
+    ($($t:ty => $p:pat => $e:expr);* $(;)?) => {
+        $(
+            impl FromCommandOption for $t {
+                fn from_option(value: Option<&OptionValue>) -> Option<Self> {
+                    match value? {
+                        $p => Some($e),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_option! {
+    String => OptionValue::String(s) => s.clone();
+    i64 => OptionValue::Integer(i) => *i;
+    f64 => OptionValue::Number(n) => *n;
+    bool => OptionValue::Boolean(b) => *b;
+    User => OptionValue::User(user, _) => user.clone();
+    UserId => OptionValue::User(user, _) => user.id;
+    PartialChannel => OptionValue::Channel(channel) => channel.clone();
+    ChannelId => OptionValue::Channel(channel) => channel.id;
+    Role => OptionValue::Role(role) => role.clone();
+    RoleId => OptionValue::Role(role) => role.id;
+}
+
+impl<T: FromCommandOption> FromCommandOption for Option<T> {
+    fn from_option(value: Option<&OptionValue>) -> Option<Self> {
+        T::from_option(value).map(Some)
+    }
+
+    fn missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+// find option by name and convert it.
+pub fn parse_option<T: FromCommandOption>(
+    options: &[ApplicationCommandInteractionDataOption],
+    name: &str,
+) -> Result<T, OptionError> {
+    let option = match options.iter().find(|option| option.name == name) {
+        Some(option) => option,
+        None => return T::missing().ok_or_else(|| OptionError::Missing(name.to_string())),
+    };
+
+    T::from_option(option.resolved.as_ref())
+        .ok_or_else(|| OptionError::InvalidType(name.to_string()))
+}
+
+// typed access to sent options, for handwritten commands.
+// interaction look up options of the invoked (sub) command.
+pub trait CommandOptionsExt {
+    fn command_options(&self) -> &[ApplicationCommandInteractionDataOption];
+
+    // `T` can be `Option<T>` for not required options.
+    fn get_option<T: FromCommandOption>(&self, name: &str) -> Result<T, OptionError> {
+        parse_option(self.command_options(), name)
+    }
+
+    fn get_str(&self, name: &str) -> Result<&str, OptionError> {
+        match resolved(self.command_options(), name)? {
+            OptionValue::String(s) => Ok(s),
+            _ => Err(OptionError::InvalidType(name.to_string())),
+        }
+    }
+
+    fn get_i64(&self, name: &str) -> Result<i64, OptionError> {
+        self.get_option(name)
+    }
+
+    fn get_f64(&self, name: &str) -> Result<f64, OptionError> {
+        self.get_option(name)
+    }
+
+    fn get_bool(&self, name: &str) -> Result<bool, OptionError> {
+        self.get_option(name)
+    }
+
+    fn get_user(&self, name: &str) -> Result<&User, OptionError> {
+        match resolved(self.command_options(), name)? {
+            OptionValue::User(user, _) => Ok(user),
+            _ => Err(OptionError::InvalidType(name.to_string())),
+        }
+    }
+
+    fn get_channel(&self, name: &str) -> Result<&PartialChannel, OptionError> {
+        match resolved(self.command_options(), name)? {
+            OptionValue::Channel(channel) => Ok(channel),
+            _ => Err(OptionError::InvalidType(name.to_string())),
+        }
+    }
+
+    fn get_role(&self, name: &str) -> Result<&Role, OptionError> {
+        match resolved(self.command_options(), name)? {
+            OptionValue::Role(role) => Ok(role),
+            _ => Err(OptionError::InvalidType(name.to_string())),
+        }
+    }
+}
+
+impl CommandOptionsExt for [ApplicationCommandInteractionDataOption] {
+    fn command_options(&self) -> &[ApplicationCommandInteractionDataOption] {
+        self
+    }
+}
+
+impl CommandOptionsExt for ApplicationCommandInteraction {
+    fn command_options(&self) -> &[ApplicationCommandInteractionDataOption] {
+        let mut options = &self.data.options[..];
+
+        while let Some(sub_command) = options.iter().find(|option| {
+            matches!(
+                option.kind,
+                ApplicationCommandOptionType::SubCommand
+                    | ApplicationCommandOptionType::SubCommandGroup
+            )
+        }) {
+            options = &sub_command.options;
+        }
+
+        options
+    }
+}
+
+// right clicked user of a user command.
+pub fn target_user(interaction: &ApplicationCommandInteraction) -> Result<&User, OptionError> {
+    match &interaction.data.target {
+        Some(ResolvedTarget::User(user, _)) => Ok(user),
+        _ => Err(OptionError::Missing("target".to_string())),
+    }
+}
+
+// right clicked message of a message command.
+pub fn target_message(
+    interaction: &ApplicationCommandInteraction,
+) -> Result<&Message, OptionError> {
+    match &interaction.data.target {
+        Some(ResolvedTarget::Message(message)) => Ok(message),
+        _ => Err(OptionError::Missing("target".to_string())),
+    }
+}
+
+fn resolved<'a>(
+    options: &'a [ApplicationCommandInteractionDataOption],
+    name: &str,
+) -> Result<&'a OptionValue, OptionError> {
+    let option = options
+        .iter()
+        .find(|option| option.name == name)
+        .ok_or_else(|| OptionError::Missing(name.to_string()))?;
+
+    option
+        .resolved
+        .as_ref()
+        .ok_or_else(|| OptionError::InvalidType(name.to_string()))
+}
+
+// access to the sent value, for options have constraints.
+pub trait OptionConstraint {
+    type Value: ?Sized;
+
+    // `None` mean the option is not sent, so nothing to check.
+    fn value(&self) -> Option<&Self::Value>;
+}
+
+impl OptionConstraint for String {
+    type Value = str;
+
+    fn value(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl OptionConstraint for i64 {
+    type Value = i64;
+
+    fn value(&self) -> Option<&i64> {
+        Some(self)
+    }
+}
+
+impl OptionConstraint for f64 {
+    type Value = f64;
+
+    fn value(&self) -> Option<&f64> {
+        Some(self)
+    }
+}
+
+impl<T: OptionConstraint> OptionConstraint for Option<T> {
+    type Value = T::Value;
+
+    fn value(&self) -> Option<&T::Value> {
+        self.as_ref().and_then(T::value)
+    }
+}
+
+// discord validate choices, but check again.
+pub fn check_choice<T>(value: T, name: &str, choices: &[&T::Value]) -> Result<T, OptionError>
+where
+    T: OptionConstraint,
+    T::Value: PartialEq,
+{
+    match value.value() {
+        Some(v) if !choices.contains(&v) => Err(OptionError::InvalidChoice(name.to_string())),
+        _ => Ok(value),
+    }
+}
+
+// discord validate range, but check again.
+pub fn check_range<T>(
+    value: T,
+    name: &str,
+    min: Option<T::Value>,
+    max: Option<T::Value>,
+) -> Result<T, OptionError>
+where
+    T: OptionConstraint,
+    T::Value: PartialOrd + Sized,
+{
+    if let Some(v) = value.value() {
+        let too_small = min.is_some_and(|min| *v < min);
+        let too_large = max.is_some_and(|max| *v > max);
+
+        if too_small || too_large {
+            return Err(OptionError::OutOfRange(name.to_string()));
+        }
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use serenity::model::channel::ChannelType;
+
+    use super::*;
+    use crate::app_cmd::fixtures::test_interaction;
+
+    fn interaction(options: Value) -> ApplicationCommandInteraction {
+        let data = json!({
+            "options": options,
+            "resolved": {
+                "users": {
+                    "700": {
+                        "id": "700",
+                        "username": "target",
+                        "discriminator": "0002",
+                        "avatar": null,
+                    },
+                },
+                "channels": {
+                    "800": {
+                        "id": "800",
+                        "name": "general",
+                        "type": 0,
+                        "permissions": "0",
+                    },
+                },
+            },
+        });
+
+        test_interaction(data, None)
+    }
+
+    fn options() -> Value {
+        json!([
+            { "name": "text", "type": 3, "value": "hello" },
+            { "name": "count", "type": 4, "value": 3 },
+            { "name": "who", "type": 6, "value": "700" },
+            { "name": "where", "type": 7, "value": "800" },
+        ])
+    }
+
+    #[test]
+    fn typed_options() {
+        let interaction = interaction(options());
+
+        assert_eq!(interaction.get_str("text"), Ok("hello"));
+        assert_eq!(interaction.get_i64("count"), Ok(3));
+        assert_eq!(interaction.get_user("who").unwrap().id, UserId(700));
+        assert_eq!(interaction.get_user("who").unwrap().name, "target");
+
+        let channel = interaction.get_channel("where").unwrap();
+        assert_eq!(channel.id, ChannelId(800));
+        assert_eq!(channel.kind, ChannelType::Text);
+    }
+
+    #[test]
+    fn missing_options() {
+        let interaction = interaction(options());
+
+        let missing = OptionError::Missing("reason".to_string());
+        assert_eq!(interaction.get_str("reason"), Err(missing.clone()));
+        assert_eq!(interaction.get_user("reason").err(), Some(missing.clone()));
+        assert_eq!(interaction.get_i64("reason"), Err(missing));
+        // not required options.
+        assert_eq!(interaction.get_option::<Option<String>>("reason"), Ok(None));
+    }
+
+    #[test]
+    fn invalid_option_types() {
+        let interaction = interaction(options());
+
+        assert_eq!(
+            interaction.get_str("count"),
+            Err(OptionError::InvalidType("count".to_string()))
+        );
+        assert_eq!(
+            interaction.get_user("text").err(),
+            Some(OptionError::InvalidType("text".to_string()))
+        );
+        assert_eq!(
+            interaction.get_channel("who").err(),
+            Some(OptionError::InvalidType("who".to_string()))
+        );
+        assert_eq!(
+            interaction.get_i64("text"),
+            Err(OptionError::InvalidType("text".to_string()))
+        );
+    }
+
+    #[test]
+    fn options_of_sub_command() {
+        // `/settings greeting set <text> <who>`
+        let interaction = interaction(json!([{
+            "name": "greeting",
+            "type": 2,
+            "options": [{
+                "name": "set",
+                "type": 1,
+                "options": [
+                    { "name": "text", "type": 3, "value": "welcome" },
+                    { "name": "who", "type": 6, "value": "700" },
+                ],
+            }],
+        }]));
+
+        assert_eq!(interaction.command_options().len(), 2);
+        assert_eq!(interaction.get_str("text"), Ok("welcome"));
+        assert_eq!(interaction.get_user("who").unwrap().id, UserId(700));
+        assert_eq!(
+            interaction.get_str("set"),
+            Err(OptionError::Missing("set".to_string()))
+        );
+    }
+
+    #[test]
+    fn options_without_sub_command() {
+        let interaction = interaction(json!([]));
+
+        assert!(interaction.command_options().is_empty());
+        assert_eq!(
+            interaction.get_str("text"),
+            Err(OptionError::Missing("text".to_string()))
+        );
+    }
+}
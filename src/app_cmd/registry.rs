@@ -0,0 +1,485 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serenity::model::{
+    id::{GuildId, UserId},
+    interactions::{
+        application_command::{
+            ApplicationCommandInteraction, ApplicationCommandInteractionData,
+            ApplicationCommandInteractionDataOption, ApplicationCommandOptionType,
+            ApplicationCommandType,
+        },
+        autocomplete::AutocompleteInteraction,
+        message_component::MessageComponentInteraction,
+    },
+    permissions::Permissions,
+};
+use serenity::prelude::*;
+
+use tracing::error;
+
+use crate::app_cmd_model::Command;
+
+use super::{
+    check_permissions, run_checks, ApplicationCommandTrait, AutocompleteChoice, AutocompleteFuture,
+    Check, CommandError, CommandResult, Component, ComponentId, ComponentResult,
+    InteractionResponder, SyncTarget,
+};
+
+// discord drop interactions not acknowledged in 3 seconds.
+const AUTO_DEFER_AFTER: Duration = Duration::from_secs(2);
+
+pub type CommandFuture<'a> = Pin<Box<dyn Future<Output = CommandResult> + Send + 'a>>;
+
+// commands on the path to the invoked one, with their paths.
+type Route = Vec<(String, &'static ApplicationCommand)>;
+
+// generated by `#[application_command]` as `ApplicationCommandTrait::META`.
+// middleware read it before running the command.
+pub struct CommandMeta {
+    pub name: &'static str,
+    pub description: &'static str,
+    // chat input, or user or message context menu.
+    pub kind: ApplicationCommandType,
+    // where it is registered, ignored for sub commands.
+    pub scope: CommandScope,
+    pub checks: &'static [&'static Check],
+    pub cooldown: Option<Duration>,
+    pub ephemeral: bool,
+    // acknowledge before running, for slow commands.
+    pub defer: bool,
+    pub required_permissions: Permissions,
+}
+
+// `#[only_in(global)]`, `#[only_in(guild(<id>, ...))]`, or `#[only_in(dev)]` by default.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommandScope {
+    Global,
+    Guilds(&'static [GuildId]),
+    // the guild in `GUILD_ID`, for testing.
+    Dev,
+}
+
+impl CommandScope {
+    pub fn includes(&self, target: SyncTarget, dev_guild: Option<GuildId>) -> bool {
+        match (self, target) {
+            (CommandScope::Global, SyncTarget::Global) => true,
+            (CommandScope::Guilds(guilds), SyncTarget::Guild(guild)) => guilds.contains(&guild),
+            (CommandScope::Dev, SyncTarget::Guild(guild)) => dev_guild == Some(guild),
+            _ => false,
+        }
+    }
+}
+
+// type erased command, so commands can be listed together.
+pub struct ApplicationCommand {
+    pub meta: CommandMeta,
+    pub model: fn() -> Command,
+    pub handler: for<'a> fn(
+        &'a Context,
+        &'a ApplicationCommandInteraction,
+        &'a InteractionResponder<'a>,
+        &'a [ApplicationCommandInteractionDataOption],
+    ) -> CommandFuture<'a>,
+    pub autocomplete: for<'a> fn(
+        &'a Context,
+        &'a AutocompleteInteraction,
+        &'a [ApplicationCommandInteractionDataOption],
+    ) -> AutocompleteFuture<'a>,
+    pub sub_commands: &'static [ApplicationCommand],
+    // generated type, to tell which submitted commands are sub commands.
+    pub type_id: fn() -> TypeId,
+}
+
+// every command submits itself, see `crate::app_cmd::commands`.
+inventory::collect!(ApplicationCommand);
+
+impl ApplicationCommand {
+    pub const fn new<T: ApplicationCommandTrait + Send + 'static>() -> Self {
+        ApplicationCommand {
+            meta: T::META,
+            model: T::model,
+            handler: |ctx, interaction, responder, options| {
+                T::options_handler(ctx, interaction, responder, options)
+            },
+            autocomplete: |ctx, interaction, options| T::autocomplete(ctx, interaction, options),
+            sub_commands: T::SUB_COMMANDS,
+            type_id: TypeId::of::<T>,
+        }
+    }
+}
+
+// two commands are registered with the same path, or two components with the same prefix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateCommand(pub String);
+
+impl fmt::Display for DuplicateCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is registered more than once", self.0)
+    }
+}
+
+impl std::error::Error for DuplicateCommand {}
+
+// commands by type and path, used for both registration and dispatch.
+// path is command name and sub command names joined by space, like `example nested pong`.
+// a slash command and a context menu command can have the same name.
+pub struct Registry {
+    // top level commands, in registration order.
+    commands: Vec<&'static ApplicationCommand>,
+    table: HashMap<(ApplicationCommandType, String), &'static ApplicationCommand>,
+    // component handlers by `custom_id` prefix.
+    components: HashMap<&'static str, &'static Component>,
+    // end of cooldown by command path and user.
+    // expired ones are removed on insert, so it holds only users in cooldown.
+    cooldowns: Mutex<HashMap<(String, UserId), Instant>>,
+}
+
+impl Registry {
+    pub fn new(
+        commands: impl IntoIterator<Item = &'static ApplicationCommand>,
+    ) -> Result<Self, DuplicateCommand> {
+        let commands = commands.into_iter().collect::<Vec<_>>();
+        let mut table = HashMap::new();
+
+        for command in &commands {
+            insert_command(&mut table, None, command)?;
+        }
+
+        Ok(Registry {
+            commands,
+            table,
+            components: HashMap::new(),
+            cooldowns: Mutex::default(),
+        })
+    }
+
+    pub fn with_components(
+        mut self,
+        components: impl IntoIterator<Item = &'static Component>,
+    ) -> Result<Self, DuplicateCommand> {
+        for component in components {
+            if self
+                .components
+                .insert(component.prefix, component)
+                .is_some()
+            {
+                return Err(DuplicateCommand(format!("{}:", component.prefix)));
+            }
+        }
+
+        Ok(self)
+    }
+
+    pub fn get(
+        &self,
+        kind: ApplicationCommandType,
+        path: &str,
+    ) -> Option<&'static ApplicationCommand> {
+        self.table.get(&(kind, path.to_string())).copied()
+    }
+
+    pub fn commands(&self) -> impl Iterator<Item = &'static ApplicationCommand> + '_ {
+        self.commands.iter().copied()
+    }
+
+    // every command and sub command with its path, for help and stats.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &'static ApplicationCommand)> {
+        self.table
+            .iter()
+            .map(|((_, path), command)| (path.as_str(), *command))
+    }
+
+    // route to the invoked (sub) command, run middleware of every command on the path,
+    // then run the command itself.
+    // the command is deferred if it is declared slow, or it does not finish in time.
+    pub async fn dispatch(
+        &self,
+        ctx: &Context,
+        interaction: &ApplicationCommandInteraction,
+        responder: &InteractionResponder<'_>,
+    ) -> CommandResult {
+        let (route, options) = self.route(&interaction.data)?;
+
+        // parent's requirements apply to its sub commands too.
+        for (_, command) in &route {
+            // default member permissions can be changed by server admins,
+            // so check again.
+            check_permissions(interaction, command.meta.required_permissions)?;
+            run_checks(ctx, interaction, command.meta.checks)
+                .await
+                .map_err(CommandError::Check)?;
+        }
+        // only count uses which passed every check.
+        for (path, command) in &route {
+            if let Some(cooldown) = command.meta.cooldown {
+                self.cool_down(path, interaction.user.id, cooldown)?;
+            }
+        }
+
+        let (path, command) = &route[route.len() - 1];
+        let ephemeral = command.meta.ephemeral;
+
+        if command.meta.defer {
+            responder.defer(ephemeral).await?;
+        }
+
+        let mut handler = (command.handler)(ctx, interaction, responder, options);
+        let result = tokio::select! {
+            result = &mut handler => result,
+            _ = tokio::time::sleep(AUTO_DEFER_AFTER) => {
+                if let Err(why) = responder.defer(ephemeral).await {
+                    error!("cannot defer slash cmd {}: {}", path, why);
+                }
+                handler.await
+            }
+        };
+
+        let mut response = result?;
+        response.ephemeral |= ephemeral;
+
+        Ok(response)
+    }
+
+    // route by `custom_id` prefix, and reject users other than the owner.
+    pub async fn dispatch_component(
+        &self,
+        ctx: &Context,
+        component: &MessageComponentInteraction,
+    ) -> ComponentResult {
+        let id = ComponentId::parse(&component.data.custom_id);
+        let (handler, id) = match id.and_then(|id| Some((self.components.get(id.prefix)?, id))) {
+            Some(found) => found,
+            None => {
+                error!("You forgot this component {}", component.data.custom_id);
+                return Err(CommandError::Other(
+                    "This component is not found, so please report to bot dev.".to_string(),
+                ));
+            }
+        };
+
+        if id.owner.is_some_and(|owner| owner != component.user.id) {
+            return Err(CommandError::Check(
+                "This component is not for you.".to_string(),
+            ));
+        }
+
+        (handler.function)(ctx, component, id.state).await
+    }
+
+    // suggestions for the focused option of the invoked (sub) command.
+    pub async fn dispatch_autocomplete(
+        &self,
+        ctx: &Context,
+        interaction: &AutocompleteInteraction,
+    ) -> Result<Vec<AutocompleteChoice>, CommandError> {
+        let (route, options) = self.route(&interaction.data)?;
+        let (_, command) = &route[route.len() - 1];
+
+        Ok((command.autocomplete)(ctx, interaction, options).await)
+    }
+
+    // every command on the path from top level command, and options of the last one.
+    fn route<'a>(
+        &self,
+        data: &'a ApplicationCommandInteractionData,
+    ) -> Result<(Route, &'a [ApplicationCommandInteractionDataOption]), CommandError> {
+        let mut path = data.name.clone();
+        let mut options = &data.options[..];
+        // names are shared, but a slash command is never invoked from a menu.
+        let mut route = vec![(path.clone(), self.find(data.kind, &path)?)];
+
+        while let Some(sub_command) = options.iter().find(|option| {
+            matches!(
+                option.kind,
+                ApplicationCommandOptionType::SubCommand
+                    | ApplicationCommandOptionType::SubCommandGroup
+            )
+        }) {
+            path.push(' ');
+            path.push_str(&sub_command.name);
+            route.push((path.clone(), self.find(data.kind, &path)?));
+            options = &sub_command.options;
+        }
+
+        Ok((route, options))
+    }
+
+    fn find(
+        &self,
+        kind: ApplicationCommandType,
+        path: &str,
+    ) -> Result<&'static ApplicationCommand, CommandError> {
+        self.get(kind, path).ok_or_else(|| {
+            error!("You forgot this {:?} cmd {}", kind, path);
+            CommandError::Other(
+                "This command is not found, so please report to bot dev.".to_string(),
+            )
+        })
+    }
+
+    fn cool_down(&self, path: &str, user: UserId, cooldown: Duration) -> Result<(), CommandError> {
+        let mut cooldowns = self.cooldowns.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let key = (path.to_string(), user);
+
+        if let Some(until) = cooldowns.get(&key) {
+            if *until > now {
+                return Err(CommandError::Cooldown(*until - now));
+            }
+        }
+
+        cooldowns.retain(|_, until| *until > now);
+        cooldowns.insert(key, now + cooldown);
+        Ok(())
+    }
+}
+
+// sub commands are always slash commands, same as their parent.
+fn insert_command(
+    table: &mut HashMap<(ApplicationCommandType, String), &'static ApplicationCommand>,
+    parent: Option<&str>,
+    command: &'static ApplicationCommand,
+) -> Result<(), DuplicateCommand> {
+    let path = match parent {
+        Some(parent) => format!("{} {}", parent, command.meta.name),
+        None => command.meta.name.to_string(),
+    };
+
+    for sub_command in command.sub_commands {
+        insert_command(table, Some(&path), sub_command)?;
+    }
+
+    if table
+        .insert((command.meta.kind, path.clone()), command)
+        .is_some()
+    {
+        return Err(DuplicateCommand(path));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::model::user::User;
+
+    use macro_util::{application_command, user_command};
+
+    use super::*;
+    use crate::app_cmd::fixtures::{RepeatCommand, SettingsCommand};
+
+    /// Reply pong.
+    #[application_command]
+    async fn ping(_ctx: &Context, _interaction: &ApplicationCommandInteraction) -> CommandResult {
+        Ok("pong".into())
+    }
+
+    // same name as the slash command, but in the user menu.
+    #[user_command("ping")]
+    async fn ping_user(
+        _ctx: &Context,
+        _interaction: &ApplicationCommandInteraction,
+        user: &User,
+    ) -> CommandResult {
+        Ok(user.tag().into())
+    }
+
+    #[test]
+    fn registry() {
+        static COMMANDS: &[ApplicationCommand] = &[
+            ApplicationCommand::new::<PingCommand>(),
+            ApplicationCommand::new::<RepeatCommand>(),
+        ];
+        let registry = Registry::new(COMMANDS).unwrap();
+
+        assert!(registry
+            .get(ApplicationCommandType::ChatInput, "repeat")
+            .is_some());
+        assert!(registry
+            .get(ApplicationCommandType::User, "repeat")
+            .is_none());
+        assert!(registry
+            .get(ApplicationCommandType::ChatInput, "settings")
+            .is_none());
+        assert_eq!(registry.commands().count(), 2);
+
+        static NESTED: &[ApplicationCommand] = &[ApplicationCommand::new::<SettingsCommand>()];
+        let registry = Registry::new(NESTED).unwrap();
+
+        let set = registry
+            .get(ApplicationCommandType::ChatInput, "settings greeting set")
+            .unwrap();
+        assert_eq!(set.meta.name, "set");
+        assert_eq!(set.meta.checks[0].name, "in_guild");
+        assert_eq!(set.meta.cooldown, Some(Duration::from_secs(5)));
+        assert!(set.meta.ephemeral);
+        assert_eq!(registry.iter().count(), 3);
+
+        static DUPLICATED: &[ApplicationCommand] = &[
+            ApplicationCommand::new::<PingCommand>(),
+            ApplicationCommand::new::<PingCommand>(),
+        ];
+        assert_eq!(
+            Registry::new(DUPLICATED).err(),
+            Some(DuplicateCommand("ping".to_string()))
+        );
+
+        static SHARED_NAME: &[ApplicationCommand] = &[
+            ApplicationCommand::new::<PingCommand>(),
+            ApplicationCommand::new::<PingUserCommand>(),
+        ];
+        let registry = Registry::new(SHARED_NAME).unwrap();
+        let menu = registry.get(ApplicationCommandType::User, "ping").unwrap();
+        assert_eq!(menu.meta.kind, ApplicationCommandType::User);
+        let slash = registry
+            .get(ApplicationCommandType::ChatInput, "ping")
+            .unwrap();
+        assert_eq!(slash.meta.kind, ApplicationCommandType::ChatInput);
+    }
+
+    #[test]
+    fn cooldown() {
+        let registry = Registry::new([]).unwrap();
+        let user = UserId(1);
+
+        assert!(registry
+            .cool_down("ping", user, Duration::from_secs(60))
+            .is_ok());
+        assert!(matches!(
+            registry.cool_down("ping", user, Duration::from_secs(60)),
+            Err(CommandError::Cooldown(left)) if left <= Duration::from_secs(60)
+        ));
+        // other commands and users are not affected.
+        assert!(registry
+            .cool_down("pong", user, Duration::from_secs(60))
+            .is_ok());
+        assert!(registry
+            .cool_down("ping", UserId(2), Duration::from_secs(60))
+            .is_ok());
+    }
+
+    #[test]
+    fn expired_cooldowns_are_removed() {
+        let registry = Registry::new([]).unwrap();
+
+        for user in 0..10 {
+            registry
+                .cool_down("ping", UserId(user), Duration::from_millis(1))
+                .unwrap();
+        }
+        std::thread::sleep(Duration::from_millis(5));
+        registry
+            .cool_down("ping", UserId(10), Duration::from_secs(60))
+            .unwrap();
+
+        let cooldowns = registry.cooldowns.lock().unwrap();
+        assert_eq!(cooldowns.len(), 1);
+        assert!(cooldowns.contains_key(&("ping".to_string(), UserId(10))));
+    }
+}
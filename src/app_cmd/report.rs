@@ -0,0 +1,27 @@
+use macro_util::message_command;
+use serenity::model::{
+    channel::Message, interactions::application_command::ApplicationCommandInteraction,
+};
+use serenity::prelude::*;
+
+use tracing::warn;
+
+use super::CommandResult;
+
+/// Report a message to moderators
+#[message_command("Report message")]
+#[ephemeral]
+pub async fn report(
+    _ctx: &Context,
+    interaction: &ApplicationCommandInteraction,
+    message: &Message,
+) -> CommandResult {
+    warn!(
+        "{} reported message by {}: {}",
+        interaction.user.tag(),
+        message.author.tag(),
+        message.link()
+    );
+
+    Ok("Thank you, the message is reported to moderators.".into())
+}
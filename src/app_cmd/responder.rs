@@ -0,0 +1,244 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serenity::http::Http;
+use serenity::model::{
+    channel::Message,
+    id::MessageId,
+    interactions::{
+        application_command::ApplicationCommandInteraction,
+        InteractionApplicationCommandCallbackDataFlags, InteractionResponseType,
+    },
+};
+use serenity::prelude::SerenityError;
+use serenity::Result as SerenityResult;
+
+use super::CommandResponse;
+
+// handle to reply to an interaction more than once.
+// commands take it by `responder: &InteractionResponder<'_>` argument.
+pub struct InteractionResponder<'a> {
+    http: &'a Http,
+    interaction: &'a ApplicationCommandInteraction,
+    // discord accept only one initial response.
+    acknowledged: AtomicBool,
+    // deferred, and "thinking..." is not replaced yet.
+    pending_deferral: AtomicBool,
+    // deferred without ephemeral, and "thinking..." is not replaced yet.
+    // edits cannot hide it, so ephemeral responses need a new message.
+    public_deferral: AtomicBool,
+}
+
+impl<'a> InteractionResponder<'a> {
+    pub fn new(http: &'a Http, interaction: &'a ApplicationCommandInteraction) -> Self {
+        InteractionResponder {
+            http,
+            interaction,
+            acknowledged: AtomicBool::new(false),
+            pending_deferral: AtomicBool::new(false),
+            public_deferral: AtomicBool::new(false),
+        }
+    }
+
+    pub fn is_acknowledged(&self) -> bool {
+        self.acknowledged.load(Ordering::SeqCst)
+    }
+
+    // show "thinking..." and get 15 minutes to respond.
+    // ephemeral cannot be changed by later edits. do nothing if already acknowledged.
+    pub async fn defer(&self, ephemeral: bool) -> SerenityResult<()> {
+        if self.acknowledged.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let result = self
+            .interaction
+            .create_interaction_response(self.http, |response| {
+                response
+                    .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                    .interaction_response_data(|data| {
+                        if ephemeral {
+                            data.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                        }
+                        data
+                    })
+            })
+            .await;
+
+        match result {
+            Ok(()) => {
+                self.pending_deferral.store(true, Ordering::SeqCst);
+                self.public_deferral.store(!ephemeral, Ordering::SeqCst);
+            }
+            Err(_) => self.acknowledged.store(false, Ordering::SeqCst),
+        }
+        result
+    }
+
+    // send as initial response, or edit it if already acknowledged.
+    // ephemeral response after public deferral is sent as follow up, see `replaces_deferral`.
+    // empty response is rejected as initial response, discord cannot show it.
+    pub async fn respond(&self, response: CommandResponse) -> SerenityResult<()> {
+        if !self.acknowledged.swap(true, Ordering::SeqCst) {
+            let result = match response.is_empty() {
+                true => Err(SerenityError::Other("cannot send an empty response")),
+                false => self.create(response).await,
+            };
+            if result.is_err() {
+                self.acknowledged.store(false, Ordering::SeqCst);
+            }
+            return result;
+        }
+
+        if response.is_empty() {
+            // "thinking..." would stay forever, so remove it.
+            if self.pending_deferral.load(Ordering::SeqCst) {
+                self.delete_original().await?;
+                self.clear_deferral();
+            }
+            // nothing to send, handler already replied by itself.
+            return Ok(());
+        }
+
+        if self.replaces_deferral(&response) {
+            // delete first, the first follow up may replace "thinking..." otherwise.
+            self.delete_original().await?;
+            return self.followup(response).await.map(|_| ());
+        }
+
+        self.edit_original(response).await.map(|_| ())
+    }
+
+    // edit original response. files are sent as a follow up,
+    // because discord cannot add files by editing.
+    // ephemeral cannot be changed, so the original visibility is kept.
+    pub async fn edit_original(&self, mut response: CommandResponse) -> SerenityResult<Message> {
+        let attachments = std::mem::take(&mut response.attachments);
+
+        let message = self
+            .interaction
+            .edit_original_interaction_response(self.http, |edit| response.fill_edit(edit))
+            .await?;
+        self.clear_deferral();
+
+        if !attachments.is_empty() {
+            let files = CommandResponse {
+                attachments,
+                ephemeral: response.ephemeral,
+                ..Default::default()
+            };
+            self.followup(files).await?;
+        }
+
+        Ok(message)
+    }
+
+    pub async fn delete_original(&self) -> SerenityResult<()> {
+        self.interaction
+            .delete_original_interaction_response(self.http)
+            .await
+    }
+
+    // first follow up replaces "thinking..." of deferral.
+    pub async fn followup(&self, response: CommandResponse) -> SerenityResult<Message> {
+        let message = self
+            .interaction
+            .create_followup_message(self.http, |followup| response.fill_followup(followup))
+            .await?;
+        self.clear_deferral();
+
+        Ok(message)
+    }
+
+    pub async fn edit_followup(
+        &self,
+        message_id: MessageId,
+        response: CommandResponse,
+    ) -> SerenityResult<Message> {
+        self.interaction
+            .edit_followup_message(self.http, message_id, |followup| {
+                response.fill_followup(followup)
+            })
+            .await
+    }
+
+    pub async fn delete_followup(&self, message_id: MessageId) -> SerenityResult<()> {
+        self.interaction
+            .delete_followup_message(self.http, message_id)
+            .await
+    }
+
+    fn clear_deferral(&self) {
+        self.pending_deferral.store(false, Ordering::SeqCst);
+        self.public_deferral.store(false, Ordering::SeqCst);
+    }
+
+    // public "thinking..." must not be edited into an ephemeral response.
+    // ephemeral deferral is kept, it is only shown to the user anyway.
+    fn replaces_deferral(&self, response: &CommandResponse) -> bool {
+        response.ephemeral && self.public_deferral.load(Ordering::SeqCst)
+    }
+
+    // initial response cannot have files, so defer and send them as follow up.
+    // first follow up replace the deferred "thinking" message.
+    async fn create(&self, response: CommandResponse) -> SerenityResult<()> {
+        if response.attachments.is_empty() {
+            return self
+                .interaction
+                .create_interaction_response(self.http, |r| {
+                    r.kind(InteractionResponseType::ChannelMessageWithSource)
+                        .interaction_response_data(|data| response.fill_data(data))
+                })
+                .await;
+        }
+
+        self.interaction
+            .create_interaction_response(self.http, |r| {
+                r.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                    .interaction_response_data(|data| {
+                        if response.ephemeral {
+                            data.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                        }
+                        data
+                    })
+            })
+            .await?;
+        self.followup(response).await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::app_cmd::fixtures::test_interaction;
+
+    #[test]
+    fn ephemeral_after_public_deferral() {
+        let http = Http::new_with_token("");
+        let interaction = test_interaction(json!({}), None);
+        let responder = InteractionResponder::new(&http, &interaction);
+        let public = CommandResponse::from("pong");
+        let ephemeral = CommandResponse::from("pong").ephemeral(true);
+
+        // not deferred, or deferred with ephemeral.
+        assert!(!responder.replaces_deferral(&ephemeral));
+
+        // as `defer(false)` does.
+        responder.acknowledged.store(true, Ordering::SeqCst);
+        responder.public_deferral.store(true, Ordering::SeqCst);
+        assert!(responder.replaces_deferral(&ephemeral));
+        assert!(!responder.replaces_deferral(&public));
+    }
+
+    #[tokio::test]
+    async fn empty_initial_response() {
+        let http = Http::new_with_token("");
+        let interaction = test_interaction(json!({}), None);
+        let responder = InteractionResponder::new(&http, &interaction);
+
+        // rejected before anything is sent, so it can still be answered.
+        assert!(responder.respond(CommandResponse::new()).await.is_err());
+        assert!(!responder.is_acknowledged());
+    }
+}
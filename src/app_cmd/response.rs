@@ -0,0 +1,208 @@
+use serenity::builder::{
+    CreateAllowedMentions, CreateComponents, CreateEmbed, CreateInteractionResponseData,
+    CreateInteractionResponseFollowup, EditInteractionResponse,
+};
+use serenity::http::AttachmentType;
+use serenity::model::interactions::InteractionApplicationCommandCallbackDataFlags;
+
+// what a command replies with.
+// `Ok("text".into())` for plain text, or build it like `CommandResponse::new().content("text")`.
+#[derive(Debug, Clone, Default)]
+pub struct CommandResponse {
+    pub content: String,
+    pub embeds: Vec<CreateEmbed>,
+    // only the invoking user can see the reply.
+    pub ephemeral: bool,
+    // `None` mean discord's default, every mention in content pings.
+    pub allowed_mentions: Option<CreateAllowedMentions>,
+    // action rows of buttons and select menus.
+    pub components: Option<CreateComponents>,
+    pub attachments: Vec<AttachmentType<'static>>,
+}
+
+impl CommandResponse {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn content(mut self, content: impl ToString) -> Self {
+        self.content = content.to_string();
+        self
+    }
+
+    pub fn embed<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut CreateEmbed) -> &mut CreateEmbed,
+    {
+        let mut embed = CreateEmbed::default();
+        f(&mut embed);
+        self.embeds.push(embed);
+        self
+    }
+
+    pub fn add_embed(mut self, embed: CreateEmbed) -> Self {
+        self.embeds.push(embed);
+        self
+    }
+
+    pub fn ephemeral(mut self, ephemeral: bool) -> Self {
+        self.ephemeral = ephemeral;
+        self
+    }
+
+    pub fn allowed_mentions<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut CreateAllowedMentions) -> &mut CreateAllowedMentions,
+    {
+        let mut allowed_mentions = CreateAllowedMentions::default();
+        f(&mut allowed_mentions);
+        self.allowed_mentions = Some(allowed_mentions);
+        self
+    }
+
+    pub fn components<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut CreateComponents) -> &mut CreateComponents,
+    {
+        let mut components = CreateComponents::default();
+        f(&mut components);
+        self.components = Some(components);
+        self
+    }
+
+    pub fn attachment(mut self, attachment: impl Into<AttachmentType<'static>>) -> Self {
+        self.attachments.push(attachment.into());
+        self
+    }
+
+    // no content, embeds, components and files.
+    pub fn is_empty(&self) -> bool {
+        self.content.is_empty()
+            && self.embeds.is_empty()
+            && self.components.is_none()
+            && self.attachments.is_empty()
+    }
+
+    pub(crate) fn fill_data<'a>(
+        &self,
+        data: &'a mut CreateInteractionResponseData,
+    ) -> &'a mut CreateInteractionResponseData {
+        if !self.content.is_empty() {
+            data.content(&self.content);
+        }
+        if !self.embeds.is_empty() {
+            data.embeds(self.embeds.iter().cloned());
+        }
+        if self.ephemeral {
+            data.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+        }
+        if let Some(allowed_mentions) = &self.allowed_mentions {
+            data.allowed_mentions(|m| {
+                *m = allowed_mentions.clone();
+                m
+            });
+        }
+        if let Some(components) = &self.components {
+            data.set_components(components.clone());
+        }
+        data
+    }
+
+    pub(crate) fn fill_followup<'a, 'b>(
+        self,
+        followup: &'b mut CreateInteractionResponseFollowup<'a>,
+    ) -> &'b mut CreateInteractionResponseFollowup<'a> {
+        if !self.content.is_empty() {
+            followup.content(self.content);
+        }
+        if !self.embeds.is_empty() {
+            followup.embeds(self.embeds);
+        }
+        if self.ephemeral {
+            followup.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+        }
+        if let Some(allowed_mentions) = self.allowed_mentions {
+            followup.allowed_mentions(|m| {
+                *m = allowed_mentions;
+                m
+            });
+        }
+        if let Some(components) = self.components {
+            followup.set_components(components);
+        }
+        followup.add_files(self.attachments)
+    }
+
+    // attachments and ephemeral cannot be edited.
+    pub(crate) fn fill_edit<'a>(
+        &self,
+        edit: &'a mut EditInteractionResponse,
+    ) -> &'a mut EditInteractionResponse {
+        if !self.content.is_empty() {
+            edit.content(&self.content);
+        }
+        if !self.embeds.is_empty() {
+            edit.set_embeds(self.embeds.clone());
+        }
+        if let Some(allowed_mentions) = &self.allowed_mentions {
+            edit.allowed_mentions(|m| {
+                *m = allowed_mentions.clone();
+                m
+            });
+        }
+        if let Some(components) = &self.components {
+            edit.components(|c| {
+                *c = components.clone();
+                c
+            });
+        }
+        edit
+    }
+}
+
+impl From<String> for CommandResponse {
+    fn from(content: String) -> Self {
+        CommandResponse {
+            content,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&str> for CommandResponse {
+    fn from(content: &str) -> Self {
+        CommandResponse {
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn response_data() {
+        let response = CommandResponse::new()
+            .content("hi")
+            .embed(|embed| embed.title("title"))
+            .ephemeral(true)
+            .allowed_mentions(|mentions| mentions.empty_parse());
+
+        let mut data = Default::default();
+        response.fill_data(&mut data);
+
+        assert_eq!(data.0["content"], "hi");
+        assert_eq!(data.0["embeds"][0]["title"], "title");
+        assert_eq!(data.0["flags"], 64);
+        assert_eq!(
+            data.0["allowed_mentions"]["parse"]
+                .as_array()
+                .unwrap()
+                .len(),
+            0
+        );
+        assert!(!data.0.contains_key("components"));
+    }
+}
@@ -0,0 +1,390 @@
+use std::fmt;
+
+use serenity::http::{request::RequestBuilder, routing::RouteInfo, Http};
+use serenity::model::id::{CommandId, GuildId};
+use serenity::Result as SerenityResult;
+
+use tracing::{error, info};
+
+use super::{CommandScope, Registry};
+use crate::app_cmd_model::{Command, RegisteredCommand, ScopedCommand};
+
+// what sync does to one command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncAction {
+    Create { command: Command },
+    Edit { id: CommandId, command: Command },
+    Delete { id: CommandId, name: String },
+    Unchanged { name: String },
+}
+
+impl fmt::Display for SyncAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncAction::Create { command } => write!(f, "create `{}`", command.name),
+            SyncAction::Edit { command, .. } => write!(f, "edit `{}`", command.name),
+            SyncAction::Delete { name, .. } => write!(f, "delete `{}`", name),
+            SyncAction::Unchanged { name } => write!(f, "keep `{}`", name),
+        }
+    }
+}
+
+// where commands are registered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SyncTarget {
+    Global,
+    Guild(GuildId),
+}
+
+impl fmt::Display for SyncTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncTarget::Global => f.write_str("global"),
+            SyncTarget::Guild(guild) => write!(f, "guild {}", guild),
+        }
+    }
+}
+
+// counts of done actions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncReport {
+    pub created: usize,
+    pub edited: usize,
+    pub deleted: usize,
+    pub unchanged: usize,
+    pub failed: usize,
+    // targets whose commands cannot be fetched, so nothing is done there.
+    pub failed_targets: usize,
+}
+
+impl SyncReport {
+    pub fn merge(&mut self, other: SyncReport) {
+        self.created += other.created;
+        self.edited += other.edited;
+        self.deleted += other.deleted;
+        self.unchanged += other.unchanged;
+        self.failed += other.failed;
+        self.failed_targets += other.failed_targets;
+    }
+}
+
+impl fmt::Display for SyncReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} created, {} edited, {} deleted, {} unchanged, {} failed",
+            self.created, self.edited, self.deleted, self.unchanged, self.failed
+        )?;
+        if self.failed_targets > 0 {
+            write!(f, ", {} targets not synced", self.failed_targets)?;
+        }
+
+        Ok(())
+    }
+}
+
+// global, and every guild some command is registered in.
+// global is always synced, so commands moved to guilds are deleted.
+// guilds no longer referred to are not visited, and keep their commands.
+// discord cannot list guilds with our commands, so `commands purge --guild <id>` removes them.
+pub fn sync_targets(registry: &Registry, dev_guild: Option<GuildId>) -> Vec<SyncTarget> {
+    let mut targets = vec![SyncTarget::Global];
+
+    for command in registry.commands() {
+        match command.meta.scope {
+            CommandScope::Guilds(guilds) => {
+                targets.extend(guilds.iter().copied().map(SyncTarget::Guild))
+            }
+            CommandScope::Dev => targets.extend(dev_guild.map(SyncTarget::Guild)),
+            CommandScope::Global => {}
+        }
+    }
+
+    targets.sort();
+    targets.dedup();
+    targets
+}
+
+// top level commands of `target`.
+pub fn desired_commands(
+    registry: &Registry,
+    target: SyncTarget,
+    dev_guild: Option<GuildId>,
+) -> Vec<Command> {
+    registry
+        .commands()
+        .filter(|command| command.meta.scope.includes(target, dev_guild))
+        .map(|command| (command.model)())
+        .collect()
+}
+
+// every top level command with its scope, as `commands export` writes.
+pub fn scoped_commands(registry: &Registry) -> Vec<ScopedCommand> {
+    registry
+        .commands()
+        .map(|command| ScopedCommand {
+            scope: command.meta.scope.into(),
+            command: (command.model)(),
+        })
+        .collect()
+}
+
+// `scoped_commands` as pretty json, for exports and the schema snapshot.
+// commands are in registry order, and `commands` sorts them, so the output is stable.
+pub fn command_schema(registry: &Registry) -> String {
+    // plain structs and strings never fail to serialize.
+    serde_json::to_string_pretty(&scoped_commands(registry))
+        .expect("command model must be serializable")
+        + "\n"
+}
+
+// compare desired commands with registered ones.
+// commands are matched by name and type, and only changed ones are sent.
+pub fn plan_sync(desired: &[Command], existing: &[RegisteredCommand]) -> Vec<SyncAction> {
+    let mut actions = Vec::new();
+
+    for command in desired {
+        let found = existing.iter().find(|e| same_command(command, &e.command));
+
+        actions.push(match found {
+            Some(e) if e.command == *command => SyncAction::Unchanged {
+                name: command.name.clone(),
+            },
+            Some(e) => SyncAction::Edit {
+                id: e.id,
+                command: command.clone(),
+            },
+            None => SyncAction::Create {
+                command: command.clone(),
+            },
+        });
+    }
+
+    for e in existing {
+        if !desired
+            .iter()
+            .any(|command| same_command(command, &e.command))
+        {
+            actions.push(SyncAction::Delete {
+                id: e.id,
+                name: e.command.name.clone(),
+            });
+        }
+    }
+
+    actions
+}
+
+// create, edit or delete commands of `target`, so they match the registry.
+// error only if registered commands cannot be fetched, failed actions are counted.
+pub async fn sync_commands(
+    http: &Http,
+    registry: &Registry,
+    target: SyncTarget,
+    dev_guild: Option<GuildId>,
+) -> SerenityResult<SyncReport> {
+    let existing = fetch_commands(http, target).await?;
+    let desired = desired_commands(registry, target, dev_guild);
+
+    Ok(apply_sync(http, target, plan_sync(&desired, &existing)).await)
+}
+
+// send planned actions to `target`.
+pub async fn apply_sync(http: &Http, target: SyncTarget, actions: Vec<SyncAction>) -> SyncReport {
+    let mut report = SyncReport::default();
+
+    for action in actions {
+        let result = match (&action, target) {
+            (SyncAction::Unchanged { .. }, _) => {
+                report.unchanged += 1;
+                continue;
+            }
+            (SyncAction::Create { command }, SyncTarget::Global) => http
+                .create_global_application_command(&to_value(command))
+                .await
+                .map(|_| report.created += 1),
+            (SyncAction::Create { command }, SyncTarget::Guild(guild)) => http
+                .create_guild_application_command(guild.0, &to_value(command))
+                .await
+                .map(|_| report.created += 1),
+            (SyncAction::Edit { id, command }, SyncTarget::Global) => http
+                .edit_global_application_command(id.0, &to_value(command))
+                .await
+                .map(|_| report.edited += 1),
+            (SyncAction::Edit { id, command }, SyncTarget::Guild(guild)) => http
+                .edit_guild_application_command(guild.0, id.0, &to_value(command))
+                .await
+                .map(|_| report.edited += 1),
+            (SyncAction::Delete { id, .. }, SyncTarget::Global) => http
+                .delete_global_application_command(id.0)
+                .await
+                .map(|_| report.deleted += 1),
+            (SyncAction::Delete { id, .. }, SyncTarget::Guild(guild)) => http
+                .delete_guild_application_command(guild.0, id.0)
+                .await
+                .map(|_| report.deleted += 1),
+        };
+
+        match result {
+            Ok(()) => info!("{} in {}", action, target),
+            Err(why) => {
+                error!("cannot {} in {}: {}", action, target, why);
+                report.failed += 1;
+            }
+        }
+    }
+
+    report
+}
+
+// serenity's model drops some fields, like `autocomplete`, so fetch as our model.
+pub async fn fetch_commands(
+    http: &Http,
+    target: SyncTarget,
+) -> SerenityResult<Vec<RegisteredCommand>> {
+    let route = match target {
+        SyncTarget::Global => RouteInfo::GetGlobalApplicationCommands {
+            application_id: http.application_id,
+        },
+        SyncTarget::Guild(guild) => RouteInfo::GetGuildApplicationCommands {
+            application_id: http.application_id,
+            guild_id: guild.0,
+        },
+    };
+
+    http.fire(RequestBuilder::new(route).build()).await
+}
+
+fn same_command(a: &Command, b: &Command) -> bool {
+    a.name == b.name && a.kind == b.kind
+}
+
+fn to_value(command: &Command) -> serde_json::Value {
+    // plain structs and strings never fail to serialize.
+    serde_json::to_value(command).expect("command model must be serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use serenity::model::interactions::application_command::ApplicationCommandInteraction;
+    use serenity::prelude::*;
+
+    use macro_util::application_command;
+
+    use super::*;
+    use crate::app_cmd::fixtures::{HelloWorldCommand, RollCommand, UserInfoCommand};
+    use crate::app_cmd::{ApplicationCommand, ApplicationCommandTrait, CommandResult};
+
+    /// Registered globally.
+    #[application_command]
+    #[only_in(global)]
+    async fn everywhere(
+        _ctx: &Context,
+        _interaction: &ApplicationCommandInteraction,
+    ) -> CommandResult {
+        Ok("everywhere".into())
+    }
+
+    /// Registered in some guilds.
+    #[application_command]
+    #[only_in(guild(2, 3))]
+    async fn some_guilds(
+        _ctx: &Context,
+        _interaction: &ApplicationCommandInteraction,
+    ) -> CommandResult {
+        Ok("here".into())
+    }
+
+    #[test]
+    fn sync_plan() {
+        static COMMANDS: &[ApplicationCommand] = &[
+            ApplicationCommand::new::<HelloWorldCommand>(),
+            ApplicationCommand::new::<RollCommand>(),
+            ApplicationCommand::new::<UserInfoCommand>(),
+        ];
+        let registry = Registry::new(COMMANDS).unwrap();
+        let dev_guild = Some(GuildId(1));
+        let desired = desired_commands(&registry, SyncTarget::Guild(GuildId(1)), dev_guild);
+
+        // as discord returns, with ids and defaults.
+        let mut hello_world = serde_json::to_value(&desired[0]).unwrap();
+        hello_world["id"] = json!("1");
+        hello_world["default_member_permissions"] = Value::Null;
+        let mut roll = serde_json::to_value(&desired[1]).unwrap();
+        roll["id"] = json!("2");
+        roll["options"][0]["max_value"] = json!(6);
+        let stale = json!({"id": "3", "type": 1, "name": "old", "description": "Old."});
+        let existing: Vec<RegisteredCommand> =
+            serde_json::from_value(json!([hello_world, roll, stale])).unwrap();
+
+        let actions = plan_sync(&desired, &existing);
+        let names = actions.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "keep `hello_world`",
+                "edit `roll`",
+                "create `User info`",
+                "delete `old`"
+            ]
+        );
+        assert!(matches!(actions[1], SyncAction::Edit { id, .. } if id.0 == 2));
+    }
+
+    #[test]
+    fn sync_scopes() {
+        static COMMANDS: &[ApplicationCommand] = &[
+            ApplicationCommand::new::<HelloWorldCommand>(),
+            ApplicationCommand::new::<EverywhereCommand>(),
+            ApplicationCommand::new::<SomeGuildsCommand>(),
+        ];
+        let registry = Registry::new(COMMANDS).unwrap();
+
+        let scope = SomeGuildsCommand::META.scope;
+        assert_eq!(scope, CommandScope::Guilds(&[GuildId(2), GuildId(3)]));
+
+        let targets = sync_targets(&registry, Some(GuildId(3)));
+        assert_eq!(
+            targets,
+            [
+                SyncTarget::Global,
+                SyncTarget::Guild(GuildId(2)),
+                SyncTarget::Guild(GuildId(3))
+            ]
+        );
+        // without `GUILD_ID`, dev commands are not registered anywhere.
+        assert_eq!(sync_targets(&registry, None).len(), 3);
+
+        let names = |target| {
+            desired_commands(&registry, target, Some(GuildId(3)))
+                .iter()
+                .map(|command| command.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(SyncTarget::Global), ["everywhere"]);
+        assert_eq!(names(SyncTarget::Guild(GuildId(2))), ["some_guilds"]);
+        assert_eq!(
+            names(SyncTarget::Guild(GuildId(3))),
+            ["hello_world", "some_guilds"]
+        );
+    }
+
+    #[test]
+    fn failed_targets_are_reported() {
+        let mut report = SyncReport {
+            created: 1,
+            ..Default::default()
+        };
+        report.merge(SyncReport {
+            failed_targets: 1,
+            ..Default::default()
+        });
+
+        assert_eq!(report.created, 1);
+        assert_eq!(
+            report.to_string(),
+            "1 created, 0 edited, 0 deleted, 0 unchanged, 0 failed, 1 targets not synced"
+        );
+    }
+}
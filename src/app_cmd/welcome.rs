@@ -0,0 +1,50 @@
+use macro_util::application_command;
+use serenity::model::{
+    interactions::application_command::ApplicationCommandInteraction, user::User,
+};
+use serenity::prelude::*;
+
+use super::{CommandResponse, CommandResult, OptionError};
+
+// choice value and the message sent for it.
+const MESSAGES: &[(&str, &str)] = &[
+    (
+        "pizza",
+        "Welcome to our cool server! Ask me if you need help",
+    ),
+    ("coffee", "Hey, do you want a coffee?"),
+    (
+        "club",
+        "Welcome to the club, you're now a good person. Well, I hope.",
+    ),
+    (
+        "game",
+        "I hope that you brought a controller to play together!",
+    ),
+];
+
+/// Welcome a user
+#[application_command]
+pub async fn welcome(
+    _ctx: &Context,
+    _interaction: &ApplicationCommandInteraction,
+    #[description = "The user to welcome"] user: User,
+    #[description = "The message to send"]
+    #[choice("Pizza", "pizza")]
+    #[choice("Coffee", "coffee")]
+    #[choice("Club", "club")]
+    #[choice("Game", "game")]
+    message: String,
+) -> CommandResult {
+    let message = match MESSAGES.iter().find(|(value, _)| *value == message) {
+        Some((_, text)) => text,
+        None => return Err(OptionError::InvalidChoice("message".to_string()).into()),
+    };
+
+    // ping only the welcomed user.
+    let response = CommandResponse::new()
+        .content(format!("{} {}", user.mention(), message))
+        .allowed_mentions(|mentions| mentions.users(vec![user.id]));
+
+    Ok(response)
+}
@@ -0,0 +1,375 @@
+// plain data of application commands, same shape as discord's json.
+// generated by `#[application_command]`, and used for builders, diffing and dumps.
+use std::fmt;
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption};
+use serenity::model::{
+    id::CommandId,
+    interactions::application_command::{ApplicationCommandOptionType, ApplicationCommandType},
+};
+
+use crate::app_cmd::CommandScope;
+
+// enum sent as its integer value.
+macro_rules! int_enum {
+    ($(#[$attr:meta])* $name:ident => $serenity:ident {
+        $($(#[$variant_attr:meta])* $variant:ident = $value:expr,)*
+    }) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        $(#[$attr])*
+        pub enum $name {
+            $($(#[$variant_attr])* $variant = $value,)*
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_u8(*self as u8)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                match u8::deserialize(deserializer)? {
+                    $($value => Ok($name::$variant),)*
+                    value => Err(D::Error::custom(format_args!(
+                        "unknown {} {}",
+                        stringify!($name),
+                        value
+                    ))),
+                }
+            }
+        }
+
+        impl From<$name> for $serenity {
+            fn from(kind: $name) -> Self {
+                match kind {
+                    $($name::$variant => $serenity::$variant,)*
+                }
+            }
+        }
+    };
+}
+
+int_enum!(#[derive(Default)] CommandKind => ApplicationCommandType {
+    #[default]
+    ChatInput = 1,
+    User = 2,
+    Message = 3,
+});
+
+int_enum!(OptionKind => ApplicationCommandOptionType {
+    SubCommand = 1,
+    SubCommandGroup = 2,
+    String = 3,
+    Integer = 4,
+    Boolean = 5,
+    User = 6,
+    Channel = 7,
+    Role = 8,
+    Mentionable = 9,
+    Number = 10,
+});
+
+// top level command. sub commands are options of their parent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Command {
+    #[serde(rename = "type", default)]
+    pub kind: CommandKind,
+    pub name: String,
+    // empty for context menu commands.
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<CommandOption>,
+    #[serde(default)]
+    pub default_member_permissions: Option<Permissions>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandOption {
+    #[serde(rename = "type")]
+    pub kind: OptionKind,
+    pub name: String,
+    pub description: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<Choice>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_value: Option<BoundValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_value: Option<BoundValue>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub autocomplete: bool,
+    // options of sub command, or sub commands of sub command group.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<CommandOption>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Choice {
+    pub name: String,
+    pub value: ChoiceValue,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChoiceValue {
+    String(String),
+    Integer(i64),
+    Number(f64),
+}
+
+// discord may return `1` for `1.0`, so numbers are compared by value.
+impl PartialEq for ChoiceValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ChoiceValue::String(a), ChoiceValue::String(b)) => a == b,
+            (ChoiceValue::String(_), _) | (_, ChoiceValue::String(_)) => false,
+            (a, b) => a.as_f64() == b.as_f64(),
+        }
+    }
+}
+
+impl ChoiceValue {
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            ChoiceValue::String(_) => None,
+            ChoiceValue::Integer(i) => Some(*i as f64),
+            ChoiceValue::Number(n) => Some(*n),
+        }
+    }
+}
+
+// `min_value` and `max_value`. integer options need integer bounds.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BoundValue {
+    Integer(i64),
+    Number(f64),
+}
+
+// discord may return `1` for `1.0`, so bounds are compared by value.
+impl PartialEq for BoundValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_f64() == other.as_f64()
+    }
+}
+
+impl BoundValue {
+    fn as_f64(self) -> f64 {
+        match self {
+            BoundValue::Integer(i) => i as f64,
+            BoundValue::Number(n) => n,
+        }
+    }
+
+    // `None` if it is not an integer, or out of `i32`.
+    fn to_i32(self) -> Option<i32> {
+        match self {
+            BoundValue::Integer(i) => i32::try_from(i).ok(),
+            BoundValue::Number(n) if n.fract() == 0.0 => i32::try_from(n as i64).ok(),
+            BoundValue::Number(_) => None,
+        }
+    }
+}
+
+// serenity's builders take only `i32` for integer choices and bounds,
+// so larger values cannot be built, while discord accepts them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntegerOutOfRange(pub String);
+
+impl fmt::Display for IntegerOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "integer choice or bound of option `{}` is out of i32",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for IntegerOutOfRange {}
+
+// permission bits, sent as string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permissions(pub u64);
+
+impl Serialize for Permissions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Permissions {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map(Permissions)
+            .map_err(D::Error::custom)
+    }
+}
+
+// where a top level command is registered.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    Global,
+    Guilds(Vec<u64>),
+    Dev,
+}
+
+impl From<CommandScope> for Scope {
+    fn from(scope: CommandScope) -> Self {
+        match scope {
+            CommandScope::Global => Scope::Global,
+            CommandScope::Guilds(guilds) => Scope::Guilds(guilds.iter().map(|g| g.0).collect()),
+            CommandScope::Dev => Scope::Dev,
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scope::Global => f.write_str("global"),
+            Scope::Guilds(guilds) => {
+                let guilds = guilds.iter().map(u64::to_string).collect::<Vec<_>>();
+                write!(f, "guilds {}", guilds.join(", "))
+            }
+            Scope::Dev => f.write_str("dev guild"),
+        }
+    }
+}
+
+// command with its scope, for dumps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScopedCommand {
+    pub scope: Scope,
+    #[serde(flatten)]
+    pub command: Command,
+}
+
+// command already registered to discord.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegisteredCommand {
+    pub id: CommandId,
+    #[serde(flatten)]
+    pub command: Command,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+impl Command {
+    pub fn build<'a>(
+        &self,
+        cmd: &'a mut CreateApplicationCommand,
+    ) -> Result<&'a mut CreateApplicationCommand, IntegerOutOfRange> {
+        cmd.name(&self.name).kind(self.kind.into());
+
+        if !self.description.is_empty() {
+            cmd.description(&self.description);
+        }
+        // members without these permissions cannot see the command by default.
+        if let Some(permissions) = self.default_member_permissions {
+            cmd.0.insert(
+                "default_member_permissions",
+                permissions.0.to_string().into(),
+            );
+        }
+        for option in &self.options {
+            let mut builder = CreateApplicationCommandOption::default();
+            option.build(&mut builder)?;
+            cmd.add_option(builder);
+        }
+
+        Ok(cmd)
+    }
+
+    // as an option of other command.
+    // command has sub commands is sub command group.
+    pub fn into_sub_command(self) -> CommandOption {
+        let kind = match self
+            .options
+            .iter()
+            .any(|option| option.kind == OptionKind::SubCommand)
+        {
+            true => OptionKind::SubCommandGroup,
+            false => OptionKind::SubCommand,
+        };
+
+        CommandOption {
+            kind,
+            name: self.name,
+            description: self.description,
+            required: false,
+            choices: Vec::new(),
+            min_value: None,
+            max_value: None,
+            autocomplete: false,
+            options: self.options,
+        }
+    }
+}
+
+impl CommandOption {
+    pub fn build<'a>(
+        &self,
+        option: &'a mut CreateApplicationCommandOption,
+    ) -> Result<&'a mut CreateApplicationCommandOption, IntegerOutOfRange> {
+        let out_of_range = || IntegerOutOfRange(self.name.clone());
+
+        option
+            .name(&self.name)
+            .description(&self.description)
+            .kind(self.kind.into());
+
+        if !matches!(
+            self.kind,
+            OptionKind::SubCommand | OptionKind::SubCommandGroup
+        ) {
+            option.required(self.required);
+        }
+        for choice in &self.choices {
+            match &choice.value {
+                ChoiceValue::String(s) => option.add_string_choice(&choice.name, s),
+                ChoiceValue::Integer(i) => {
+                    let i = i32::try_from(*i).map_err(|_| out_of_range())?;
+                    option.add_int_choice(&choice.name, i)
+                }
+                ChoiceValue::Number(n) => option.add_number_choice(&choice.name, *n),
+            };
+        }
+        match self.kind {
+            OptionKind::Integer => {
+                if let Some(min) = self.min_value {
+                    option.min_int_value(min.to_i32().ok_or_else(out_of_range)?);
+                }
+                if let Some(max) = self.max_value {
+                    option.max_int_value(max.to_i32().ok_or_else(out_of_range)?);
+                }
+            }
+            _ => {
+                if let Some(min) = self.min_value {
+                    option.min_number_value(min.as_f64());
+                }
+                if let Some(max) = self.max_value {
+                    option.max_number_value(max.as_f64());
+                }
+            }
+        }
+        if self.autocomplete {
+            option.set_autocomplete(true);
+        }
+        for sub_option in &self.options {
+            let mut builder = CreateApplicationCommandOption::default();
+            sub_option.build(&mut builder)?;
+            option.add_sub_option(builder);
+        }
+
+        Ok(option)
+    }
+}
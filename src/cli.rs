@@ -0,0 +1,221 @@
+// `commands ...` subcommands of the binary.
+// they use only the http client, so commands can be managed without connecting to the gateway.
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serenity::http::Http;
+use serenity::model::id::GuildId;
+use serenity::prelude::SerenityError;
+
+use crate::app_cmd::{
+    apply_sync, command_schema, commands, desired_commands, dev_guild, fetch_commands, plan_sync,
+    setup_app_cmd, sync_targets, DuplicateCommand, Registry, SyncTarget,
+};
+use crate::Config;
+
+pub const USAGE: &str = "\
+usage:
+    serenity_discord_bot_test                          run the bot
+    serenity_discord_bot_test commands list            list registered commands
+    serenity_discord_bot_test commands sync [--dry-run]
+                                                       register commands, or show what would change
+    serenity_discord_bot_test commands purge --guild <id>
+                                                       delete every command of the guild,
+                                                       sync does not visit guilds no longer used
+    serenity_discord_bot_test commands export <file>   write command definitions as json";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    List,
+    Sync { dry_run: bool },
+    Purge { guild: GuildId },
+    Export { path: PathBuf },
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    // wrong arguments, shown with usage.
+    Usage(String),
+    Serenity(SerenityError),
+    Registry(DuplicateCommand),
+    Io(io::Error),
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(why) => write!(f, "{}\n\n{}", why, USAGE),
+            CliError::Serenity(why) => why.fmt(f),
+            CliError::Registry(why) => why.fmt(f),
+            CliError::Io(why) => why.fmt(f),
+            CliError::Json(why) => why.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<SerenityError> for CliError {
+    fn from(why: SerenityError) -> Self {
+        CliError::Serenity(why)
+    }
+}
+
+impl From<DuplicateCommand> for CliError {
+    fn from(why: DuplicateCommand) -> Self {
+        CliError::Registry(why)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(why: io::Error) -> Self {
+        CliError::Io(why)
+    }
+}
+
+impl From<serde_json::Error> for CliError {
+    fn from(why: serde_json::Error) -> Self {
+        CliError::Json(why)
+    }
+}
+
+impl CliCommand {
+    // `args` is without the program name. no arguments mean running the bot.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, CliError> {
+        let mut args = args.into_iter();
+        let usage = |why: &str| Err(CliError::Usage(why.to_string()));
+
+        match args.next().as_deref() {
+            None => return Ok(None),
+            Some("commands") => {}
+            Some(other) => return usage(&format!("unknown command `{}`", other)),
+        }
+
+        let command = match args.next().as_deref() {
+            Some("list") => CliCommand::List,
+            Some("sync") => match args.next().as_deref() {
+                None => CliCommand::Sync { dry_run: false },
+                Some("--dry-run") => CliCommand::Sync { dry_run: true },
+                Some(other) => return usage(&format!("unknown flag `{}`", other)),
+            },
+            Some("purge") => match (args.next().as_deref(), args.next()) {
+                (Some("--guild"), Some(id)) => match id.parse() {
+                    Ok(id) => CliCommand::Purge { guild: GuildId(id) },
+                    Err(_) => return usage(&format!("guild id must be an integer: `{}`", id)),
+                },
+                _ => return usage("purge needs `--guild <id>`"),
+            },
+            Some("export") => match args.next() {
+                Some(path) => CliCommand::Export { path: path.into() },
+                None => return usage("export needs an output file"),
+            },
+            Some(other) => return usage(&format!("unknown subcommand `{}`", other)),
+            None => return usage("missing subcommand"),
+        };
+
+        match args.next() {
+            Some(extra) => usage(&format!("unexpected argument `{}`", extra)),
+            None => Ok(Some(command)),
+        }
+    }
+}
+
+// nothing is sent, so it needs neither the token nor `Config`.
+pub fn export_commands(path: &Path) -> Result<(), CliError> {
+    let registry = Registry::new(commands())?;
+
+    fs::write(path, command_schema(&registry))?;
+    println!(
+        "exported {} commands to {}",
+        registry.commands().count(),
+        path.display()
+    );
+
+    Ok(())
+}
+
+pub async fn run_cli(config: &Config, command: CliCommand) -> Result<(), CliError> {
+    if let CliCommand::Export { path } = &command {
+        return export_commands(path);
+    }
+
+    let registry = Registry::new(commands())?;
+    let http = connect(&config.token).await?;
+    let dev_guild = dev_guild();
+
+    match command {
+        CliCommand::List => {
+            for target in sync_targets(&registry, dev_guild) {
+                let desired = desired_commands(&registry, target, dev_guild);
+                println!("{}:", target);
+                for registered in fetch_commands(&http, target).await? {
+                    let known = desired.iter().any(|command| {
+                        command.name == registered.command.name
+                            && command.kind == registered.command.kind
+                    });
+                    println!(
+                        "    {} ({}){}",
+                        registered.command.name,
+                        registered.id,
+                        if known { "" } else { " not in registry" }
+                    );
+                }
+            }
+        }
+        CliCommand::Sync { dry_run: true } => {
+            for target in sync_targets(&registry, dev_guild) {
+                let existing = fetch_commands(&http, target).await?;
+                let desired = desired_commands(&registry, target, dev_guild);
+                for action in plan_sync(&desired, &existing) {
+                    println!("{}: {}", target, action);
+                }
+            }
+        }
+        CliCommand::Sync { dry_run: false } => {
+            let report = setup_app_cmd(&registry, &http).await;
+            println!("synced commands: {}", report);
+        }
+        CliCommand::Purge { guild } => {
+            let target = SyncTarget::Guild(guild);
+            let existing = fetch_commands(&http, target).await?;
+            let report = apply_sync(&http, target, plan_sync(&[], &existing)).await;
+            println!("purged {}: {}", target, report);
+        }
+        CliCommand::Export { .. } => unreachable!("export is handled above"),
+    }
+
+    Ok(())
+}
+
+// application id is needed for command endpoints, the gateway usually provides it.
+async fn connect(token: &str) -> Result<Http, SerenityError> {
+    let mut http = Http::new_with_token(token);
+    http.application_id = http.get_current_application_info().await?.id.0;
+
+    Ok(http)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli_args() {
+        let parse = |args: &[&str]| CliCommand::parse(args.iter().map(ToString::to_string));
+
+        assert_eq!(parse(&[]).unwrap(), None);
+        assert_eq!(
+            parse(&["commands", "sync", "--dry-run"]).unwrap(),
+            Some(CliCommand::Sync { dry_run: true })
+        );
+        assert_eq!(
+            parse(&["commands", "purge", "--guild", "42"]).unwrap(),
+            Some(CliCommand::Purge { guild: GuildId(42) })
+        );
+        assert!(parse(&["commands", "purge"]).is_err());
+        assert!(parse(&["commands", "list", "extra"]).is_err());
+    }
+}
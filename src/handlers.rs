@@ -1,23 +1,49 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use serenity::async_trait;
 use serenity::client::EventHandler;
 use serenity::model::{gateway::Ready, interactions::Interaction};
 use serenity::prelude::*;
 
-use tracing::info;
+use tracing::{error, info};
+
+use crate::app_cmd::{interaction_handler, setup_app_cmd, Registry};
 
-use crate::app_cmd::{interaction_handler, setup_app_cmd};
+pub struct Handler {
+    registry: Registry,
+    // ready is sent again on every reconnect, but commands are synced only once.
+    synced: AtomicBool,
+}
 
-pub struct Handler;
+impl Handler {
+    pub fn new(registry: Registry) -> Self {
+        Handler {
+            registry,
+            synced: AtomicBool::new(false),
+        }
+    }
+}
 
 #[async_trait]
 impl EventHandler for Handler {
     async fn ready(&self, ctx: Context, ready: Ready) {
         // Log at the INFO level. This is a macro from the `tracing` crate.
         info!("{} is connected!", ready.user.name);
-        setup_app_cmd(&ctx).await;
+        if self.synced.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let report = setup_app_cmd(&self.registry, &ctx.http).await;
+        if report.failed_targets > 0 {
+            error!("cannot sync every target: {}", report);
+            // try again on next ready.
+            self.synced.store(false, Ordering::SeqCst);
+        } else {
+            info!("synced commands: {}", report);
+        }
     }
 
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
-        interaction_handler(ctx, interaction).await;
+        interaction_handler(&self.registry, ctx, interaction).await;
     }
 }
@@ -1,8 +1,11 @@
-mod app_cmd;
+pub mod app_cmd;
+pub mod app_cmd_model;
+mod cli;
 mod commands;
 mod handlers;
 
 use std::env;
+use std::fmt;
 use std::io;
 
 use serenity::client::Client;
@@ -20,6 +23,8 @@ use tracing_subscriber::{
     registry,
 };
 
+use app_cmd::{commands, components, DuplicateCommand, Registry};
+pub use cli::{export_commands, run_cli, CliCommand, CliError, USAGE};
 use commands::get_groups;
 use handlers::Handler;
 
@@ -62,7 +67,37 @@ pub fn logging_init(config: &Config) -> Result<(), SetGlobalDefaultError> {
     set_global_default(subscriber)
 }
 
-pub async fn bot_builder(config: Config) -> Result<Client, SerenityError> {
+#[derive(Debug)]
+pub enum BuildError {
+    Serenity(SerenityError),
+    // slash commands cannot be registered.
+    Registry(DuplicateCommand),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::Serenity(why) => why.fmt(f),
+            BuildError::Registry(why) => why.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+impl From<SerenityError> for BuildError {
+    fn from(why: SerenityError) -> Self {
+        BuildError::Serenity(why)
+    }
+}
+
+impl From<DuplicateCommand> for BuildError {
+    fn from(why: DuplicateCommand) -> Self {
+        BuildError::Registry(why)
+    }
+}
+
+pub async fn bot_builder(config: Config) -> Result<Client, BuildError> {
     let mut framework = StandardFramework::new().configure(|c| {
         //set prefix
         c.prefix(config.prefix)
@@ -73,8 +108,11 @@ pub async fn bot_builder(config: Config) -> Result<Client, SerenityError> {
         framework = framework.group(cmd_group);
     }
 
+    // fail before connecting, if commands conflict.
+    let registry = Registry::new(commands())?.with_components(components())?;
+
     let client = Client::builder(&config.token)
-        .event_handler(Handler)
+        .event_handler(Handler::new(registry))
         .framework(framework)
         .await?;
 
@@ -83,18 +121,162 @@ pub async fn bot_builder(config: Config) -> Result<Client, SerenityError> {
 
 #[cfg(test)]
 mod tests {
+    use serde_json::json;
+    use serenity::model::interactions::application_command::ApplicationCommandType;
+
+    use crate::app_cmd::fixtures::*;
+    use crate::app_cmd::{
+        check_choice, check_range, ApplicationCommandTrait, AutocompleteChoice, AutocompleteValue,
+    };
+    use crate::app_cmd_model::{BoundValue, IntegerOutOfRange};
+
+    #[test]
+    fn generated_command() {
+        assert_eq!(HelloWorldCommand::META.name, "hello_world");
+
+        let mut cmd = Default::default();
+        HelloWorldCommand::setup_app_cmd(&mut cmd).unwrap();
+
+        assert_eq!(cmd.0["name"], "hello_world");
+        assert_eq!(cmd.0["description"], "Say hello\nto the world.");
+    }
+
     #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
+    fn generated_options() {
+        let mut cmd = Default::default();
+        RepeatCommand::setup_app_cmd(&mut cmd).unwrap();
 
-        hello_world();
+        let options = cmd.0["options"].as_array().unwrap();
+        assert_eq!(options.len(), 2);
+        assert_eq!(options[0]["name"], "message");
+        assert_eq!(options[0]["type"], 3);
+        assert_eq!(options[0]["required"], true);
+        assert_eq!(options[1]["name"], "times");
+        assert_eq!(options[1]["type"], 4);
+        assert_eq!(options[1]["required"], false);
     }
 
-    use macro_util::application_command;
+    #[test]
+    fn generated_choices() {
+        let mut cmd = Default::default();
+        DrinkCommand::setup_app_cmd(&mut cmd).unwrap();
+
+        let choices = cmd.0["options"][0]["choices"].as_array().unwrap();
+        assert_eq!(choices.len(), 2);
+        assert_eq!(choices[0]["name"], "Coffee");
+        assert_eq!(choices[0]["value"], "coffee");
+
+        let choices: &[&str] = &["coffee", "tea"];
+        assert!(check_choice("tea".to_string(), "drink", choices).is_ok());
+        assert!(check_choice("milk".to_string(), "drink", choices).is_err());
+        assert!(check_choice(None::<String>, "drink", choices).is_ok());
+    }
+
+    #[test]
+    fn generated_ranges() {
+        let mut cmd = Default::default();
+        RollCommand::setup_app_cmd(&mut cmd).unwrap();
+
+        let options = cmd.0["options"].as_array().unwrap();
+        assert_eq!(options[0]["min_value"], 1);
+        assert_eq!(options[0]["max_value"], 100);
+        assert_eq!(options[1]["min_value"], -0.5);
+        assert!(options[1].get("max_value").is_none());
+
+        // exports and sync compare the model, integer bounds must stay integers.
+        let model = serde_json::to_value(RollCommand::model()).unwrap();
+        assert_eq!(model["options"][0]["min_value"], json!(1));
+        assert_eq!(model["options"][0]["max_value"], json!(100));
+        assert_eq!(model["options"][1]["min_value"], json!(-0.5));
+        assert_eq!(BoundValue::Number(6.0), BoundValue::Integer(6));
+
+        // a model from json may not fit the builder, it is not truncated.
+        let mut model = RollCommand::model();
+        model.options[0].max_value = Some(BoundValue::Integer(1 << 40));
+        let error = model.build(&mut Default::default()).err();
+        assert_eq!(error, Some(IntegerOutOfRange("faces".to_string())));
+        model.options[0].max_value = Some(BoundValue::Number(1.5));
+        assert!(model.build(&mut Default::default()).is_err());
+
+        assert!(check_range(5i64, "faces", Some(1), Some(100)).is_ok());
+        assert!(check_range(101i64, "faces", Some(1), Some(100)).is_err());
+        assert!(check_range(Some(-1.0f64), "bias", Some(-0.5), None).is_err());
+        assert!(check_range(None::<f64>, "bias", Some(-0.5), None).is_ok());
+    }
+
+    #[test]
+    fn generated_autocomplete() {
+        let mut cmd = Default::default();
+        SearchCommand::setup_app_cmd(&mut cmd).unwrap();
+        assert_eq!(cmd.0["options"][0]["autocomplete"], true);
+
+        let mut cmd = Default::default();
+        RepeatCommand::setup_app_cmd(&mut cmd).unwrap();
+        assert!(cmd.0["options"][0].get("autocomplete").is_none());
+
+        assert_eq!(
+            AutocompleteChoice::from("tea"),
+            AutocompleteChoice::new("tea", AutocompleteValue::String("tea".to_string()))
+        );
+    }
+
+    #[test]
+    fn generated_sub_commands() {
+        assert_eq!(SettingsCommand::DEPTH, 2);
+
+        let mut cmd = Default::default();
+        SettingsCommand::setup_app_cmd(&mut cmd).unwrap();
+
+        let group = &cmd.0["options"][0];
+        assert_eq!(group["name"], "greeting");
+        assert_eq!(group["type"], 2);
+
+        let sub_command = &group["options"][0];
+        assert_eq!(sub_command["name"], "set");
+        assert_eq!(sub_command["type"], 1);
+        assert_eq!(sub_command["options"][0]["name"], "text");
+    }
+
+    #[test]
+    fn generated_check() {
+        assert_eq!(IN_GUILD_CHECK.name, "in_guild");
+    }
+
+    #[test]
+    fn generated_permissions() {
+        let mut cmd = Default::default();
+        SettingsCommand::setup_app_cmd(&mut cmd).unwrap();
+
+        // BAN_MEMBERS | KICK_MEMBERS | MANAGE_GUILD
+        assert_eq!(cmd.0["default_member_permissions"], "38");
+    }
+
+    #[test]
+    fn generated_responder() {
+        let (slow, hello_world) = (SlowCommand::META, HelloWorldCommand::META);
+        assert!(slow.defer);
+        assert!(!hello_world.defer);
+
+        let mut cmd = Default::default();
+        SlowCommand::setup_app_cmd(&mut cmd).unwrap();
+
+        let options = cmd.0["options"].as_array().unwrap();
+        assert_eq!(options.len(), 1);
+        assert_eq!(options[0]["name"], "message");
+    }
+
+    #[test]
+    fn generated_context_menu() {
+        let kind = UserInfoCommand::META.kind;
+        assert_eq!(kind, ApplicationCommandType::User);
+
+        let mut cmd = Default::default();
+        UserInfoCommand::setup_app_cmd(&mut cmd).unwrap();
+        assert_eq!(cmd.0["name"], "User info");
+        assert_eq!(cmd.0["type"], 2);
+        assert!(!cmd.0.contains_key("description"));
 
-    #[hi]
-    #[application_command]
-    fn hello_world() {
-        println!("Hello World!");
+        let kind = HelloWorldCommand::META.kind;
+        assert_eq!(kind, ApplicationCommandType::ChatInput);
     }
 }
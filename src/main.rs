@@ -1,13 +1,32 @@
 extern crate serenity_discord_bot_test;
 
+use std::env;
+use std::process;
+
 use dotenv::dotenv;
-use serenity_discord_bot_test::{bot_builder, logging_init, Config};
+use serenity_discord_bot_test::{
+    bot_builder, export_commands, logging_init, run_cli, CliCommand, Config,
+};
 
 #[tokio::main]
 async fn main() {
     //load env
     dotenv().ok();
 
+    // parse subcommands, before anything is set up
+    let command = CliCommand::parse(env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(2);
+    });
+
+    // export works offline, so it runs before the token is required
+    if let Some(CliCommand::Export { path }) = &command {
+        if let Err(why) = export_commands(path) {
+            panic!("An error occurred while running the command: {}", why);
+        }
+        return;
+    }
+
     // gen config
     let config = Config::new().unwrap_or_else(|err| {
         panic!("An error occured create config struct: {}", err);
@@ -18,6 +37,14 @@ async fn main() {
         panic!("An error occurred setup: {:?}", why);
     }
 
+    // manage commands over http only, without the gateway
+    if let Some(command) = command {
+        if let Err(why) = run_cli(&config, command).await {
+            panic!("An error occurred while running the command: {}", why);
+        }
+        return;
+    }
+
     // build bot
     let mut bot = bot_builder(config).await.unwrap_or_else(|err| {
         panic!("An error occured build the bot: {:?}", err);
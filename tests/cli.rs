@@ -0,0 +1,32 @@
+use std::env;
+use std::fs;
+use std::process::Command;
+
+use serde_json::Value;
+
+// export must work without `DISCORD_TOKEN`, like on CI.
+#[test]
+fn export_without_token() {
+    // outside of the repository, so `.env` is not loaded.
+    let dir = env::temp_dir().join(format!("export_without_token_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("commands.json");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_serenity_discord_bot_test"))
+        .args(["commands", "export"])
+        .arg(&path)
+        .current_dir(&dir)
+        .env_remove("DISCORD_TOKEN")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let schema: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(!schema.as_array().unwrap().is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
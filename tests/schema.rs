@@ -0,0 +1,36 @@
+use serenity_discord_bot_test::app_cmd::{command_schema, commands, Registry};
+
+// fails on any change of registered commands, like names, options, choices or ranges.
+// run with `UPDATE_SNAPSHOT=1` to accept the change, and review the diff of the snapshot.
+// it is an integration test, so commands of unit tests are not submitted.
+#[test]
+fn command_schema_snapshot() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/commands.json");
+    let schema = command_schema(&Registry::new(commands()).unwrap());
+
+    if std::env::var_os("UPDATE_SNAPSHOT").is_some() {
+        std::fs::write(path, &schema).unwrap();
+        return;
+    }
+    let snapshot = std::fs::read_to_string(path).unwrap_or_default();
+    assert!(
+        snapshot == schema,
+        "command schema is changed, run with `UPDATE_SNAPSHOT=1` if it is intended.\n\
+         snapshot:\n{}\nactual:\n{}",
+        snapshot,
+        schema
+    );
+}
+
+#[test]
+fn sub_commands_are_not_top_level() {
+    let names = commands()
+        .iter()
+        .map(|command| command.meta.name)
+        .collect::<Vec<_>>();
+
+    assert!(names.contains(&"example"));
+    for sub_command in ["echo", "nested", "pong"] {
+        assert!(!names.contains(&sub_command), "{}", sub_command);
+    }
+}
@@ -0,0 +1,7 @@
+// compile errors of the attribute macros, checked against `tests/ui/*.stderr`.
+// run with `TRYBUILD=overwrite` to accept changed messages, and review the diff.
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
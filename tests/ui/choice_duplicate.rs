@@ -0,0 +1,16 @@
+use macro_util::application_command;
+
+/// Pick a color
+#[application_command]
+async fn color(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+    #[description = "The color"]
+    #[choice("Red", "red")]
+    #[choice("Also red", "red")]
+    color: String,
+) -> String {
+    color
+}
+
+fn main() {}
@@ -0,0 +1,40 @@
+use macro_util::application_command;
+
+/// Pick a number
+#[application_command]
+async fn pick(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+    #[description = "The number"]
+    #[choice("Choice 0", 0)]
+    #[choice("Choice 1", 1)]
+    #[choice("Choice 2", 2)]
+    #[choice("Choice 3", 3)]
+    #[choice("Choice 4", 4)]
+    #[choice("Choice 5", 5)]
+    #[choice("Choice 6", 6)]
+    #[choice("Choice 7", 7)]
+    #[choice("Choice 8", 8)]
+    #[choice("Choice 9", 9)]
+    #[choice("Choice 10", 10)]
+    #[choice("Choice 11", 11)]
+    #[choice("Choice 12", 12)]
+    #[choice("Choice 13", 13)]
+    #[choice("Choice 14", 14)]
+    #[choice("Choice 15", 15)]
+    #[choice("Choice 16", 16)]
+    #[choice("Choice 17", 17)]
+    #[choice("Choice 18", 18)]
+    #[choice("Choice 19", 19)]
+    #[choice("Choice 20", 20)]
+    #[choice("Choice 21", 21)]
+    #[choice("Choice 22", 22)]
+    #[choice("Choice 23", 23)]
+    #[choice("Choice 24", 24)]
+    #[choice("Choice 25", 25)]
+    number: i64,
+) -> String {
+    number.to_string()
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use macro_util::application_command;
+
+/// Pick things
+#[application_command]
+async fn pick(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+    #[description = "A color"]
+    #[choice("Red", 1)]
+    color: String,
+    #[description = "A number"]
+    #[choice("One", 1.5)]
+    number: i64,
+    #[description = "A flag"]
+    #[choice("Yes", true)]
+    flag: bool,
+) -> String {
+    format!("{} {} {}", color, number, flag)
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+use macro_util::application_command;
+
+#[application_command]
+async fn undocumented(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+    text: String,
+) -> String {
+    text
+}
+
+/// A description that goes on and on, well past the one hundred characters that discord accepts for the description of a command
+#[application_command]
+async fn verbose(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+    #[description = ""] text: String,
+) -> String {
+    text
+}
+
+fn main() {}
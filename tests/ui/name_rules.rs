@@ -0,0 +1,31 @@
+use macro_util::application_command;
+
+/// Uppercase is not allowed
+#[application_command("Ping")]
+async fn ping(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+) -> String {
+    String::new()
+}
+
+/// Names are at most 32 characters
+#[application_command]
+async fn a_command_name_that_is_far_too_long(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+) -> String {
+    String::new()
+}
+
+/// Spaces are not allowed
+#[application_command("say hi")]
+async fn say(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+    #[description = "Who to greet"] Who: String,
+) -> String {
+    String::new()
+}
+
+fn main() {}
@@ -0,0 +1,28 @@
+use macro_util::{app_check, app_component, application_command};
+
+// every error of a command is reported, not only the first one.
+/// Send a greeting
+#[application_command("hello", "hi", only_in(everywhere), ephemeral = 1)]
+#[cooldown = "5"]
+async fn hello(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+    #[description = ""] Name: String,
+) -> String {
+    String::new()
+}
+
+#[app_check]
+#[cooldown = 5]
+#[ephemeral]
+async fn in_guild(_ctx: &serenity::client::Context) -> Result<(), String> {
+    Ok(())
+}
+
+#[app_component(1)]
+#[ephemeral]
+async fn pager(_ctx: &serenity::client::Context) -> ComponentResult {
+    unimplemented!()
+}
+
+fn main() {}
@@ -0,0 +1,38 @@
+use macro_util::application_command;
+
+/// Too many options
+#[application_command]
+async fn many(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+    #[description = "Option 1"] o1: i64,
+    #[description = "Option 2"] o2: i64,
+    #[description = "Option 3"] o3: i64,
+    #[description = "Option 4"] o4: i64,
+    #[description = "Option 5"] o5: i64,
+    #[description = "Option 6"] o6: i64,
+    #[description = "Option 7"] o7: i64,
+    #[description = "Option 8"] o8: i64,
+    #[description = "Option 9"] o9: i64,
+    #[description = "Option 10"] o10: i64,
+    #[description = "Option 11"] o11: i64,
+    #[description = "Option 12"] o12: i64,
+    #[description = "Option 13"] o13: i64,
+    #[description = "Option 14"] o14: i64,
+    #[description = "Option 15"] o15: i64,
+    #[description = "Option 16"] o16: i64,
+    #[description = "Option 17"] o17: i64,
+    #[description = "Option 18"] o18: i64,
+    #[description = "Option 19"] o19: i64,
+    #[description = "Option 20"] o20: i64,
+    #[description = "Option 21"] o21: i64,
+    #[description = "Option 22"] o22: i64,
+    #[description = "Option 23"] o23: i64,
+    #[description = "Option 24"] o24: i64,
+    #[description = "Option 25"] o25: i64,
+    #[description = "Option 26"] o26: i64,
+) -> String {
+    String::new()
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use macro_util::application_command;
+
+/// Required option after an optional one
+#[application_command]
+async fn order(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+    #[description = "First"] first: i64,
+    #[description = "Second"] second: Option<i64>,
+    #[description = "Third"] third: String,
+    #[description = "Fourth"] fourth: bool,
+) -> String {
+    String::new()
+}
+
+fn main() {}
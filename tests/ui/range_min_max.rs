@@ -0,0 +1,20 @@
+use macro_util::application_command;
+
+/// Roll a dice
+#[application_command]
+async fn roll(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+    #[description = "Number of sides"]
+    #[min = 6]
+    #[max = 1]
+    sides: i64,
+    #[description = "Bias"]
+    #[min = 0.5]
+    #[max = -0.5]
+    bias: f64,
+) -> String {
+    format!("{} {}", sides, bias)
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+use macro_util::application_command;
+
+/// Roll a dice
+#[application_command]
+async fn roll(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+    #[description = "Number of sides"]
+    #[min = 1.5]
+    #[max = 3000000000]
+    sides: i64,
+    #[description = "Bias"]
+    #[min = "low"]
+    bias: f64,
+    #[description = "Label"]
+    #[max = 10]
+    label: String,
+) -> String {
+    format!("{} {} {}", sides, bias, label)
+}
+
+fn main() {}
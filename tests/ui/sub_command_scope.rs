@@ -0,0 +1,30 @@
+use macro_util::application_command;
+
+// generated code refers to these modules of the bot crate.
+mod app_cmd {
+    pub use serenity_discord_bot_test::app_cmd::*;
+}
+mod app_cmd_model {
+    pub use serenity_discord_bot_test::app_cmd_model::*;
+}
+
+/// Parent
+#[application_command(sub_commands(child))]
+async fn parent(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+) -> serenity_discord_bot_test::app_cmd::CommandResult {
+    Ok("".into())
+}
+
+/// Child
+#[application_command]
+#[only_in(global)]
+async fn child(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+) -> serenity_discord_bot_test::app_cmd::CommandResult {
+    Ok("".into())
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use macro_util::application_command;
+
+/// Shut down the bot
+#[application_command]
+#[owners_only]
+#[allowed_roles("admin")]
+#[bucket = "slow"]
+#[aliases("stop", "halt")]
+async fn shutdown(
+    _ctx: &serenity::client::Context,
+    _interaction: &serenity::model::interactions::application_command::ApplicationCommandInteraction,
+) -> String {
+    String::new()
+}
+
+fn main() {}